@@ -0,0 +1,90 @@
+//! Compares writing colorized values with `write!` to writing them with `write_to`,
+//! and displaying styled values to displaying them with a compiled `Paint`.
+//!
+//! Run with `cargo bench`. This doesn't use a benchmarking framework, so the numbers
+//! are only rough, but the difference between the two paths is large enough to show.
+use chromaterm::prelude::*;
+use chromaterm::{ColorSupport, Paint, Spec, Styled};
+use std::hint::black_box;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 200_000;
+
+/// Runs the closure many times, writing to a reused buffer, and returns the average
+/// time per iteration.
+fn measure<F: Fn(&mut Vec<u8>)>(run: F) -> Duration {
+    let mut out = Vec::with_capacity(256);
+    // NOTE Warm up, so the buffer and caches are ready.
+    for _ in 0..ITERATIONS / 10 {
+        out.clear();
+        run(&mut out);
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        out.clear();
+        run(black_box(&mut out));
+    }
+    black_box(&out);
+    start.elapsed() / ITERATIONS
+}
+
+/// Measures both paths for the value, and prints the results.
+fn compare<D: WriteTo + DisplayWithFallback>(name: &str, value: D, supported: ColorSupport) {
+    let fmt =
+        measure(|out| write!(out, "{}", black_box(&value).display_fallback(supported)).unwrap());
+    let write_to = measure(|out| black_box(&value).write_to(out, supported).unwrap());
+    let speedup = fmt.as_secs_f64() / write_to.as_secs_f64();
+    println!("{name:<24} fmt {fmt:>8?}  write_to {write_to:>8?}  {speedup:.2}x");
+}
+
+/// Measures displaying a styled value, and displaying it with a compiled paint.
+fn compare_paint(name: &str, spec: Spec, supported: ColorSupport) {
+    let value = "cell";
+    let styled = measure(|out| {
+        let styled = Styled::new(black_box(value), spec);
+        write!(out, "{}", styled.display_fallback(supported)).unwrap();
+    });
+    let paint = Paint::compile(spec, supported);
+    let painted = measure(|out| write!(out, "{}", paint.paint(black_box(value))).unwrap());
+    let speedup = styled.as_secs_f64() / painted.as_secs_f64();
+    println!("{name:<24} styled {styled:>8?}  paint {painted:>8?}  {speedup:.2}x");
+}
+
+fn main() {
+    let message = "the quick brown fox";
+    compare("simple", message.red(), ColorSupport::Simple);
+    compare(
+        "simple + style",
+        message.bright_blue().bold(),
+        ColorSupport::Simple,
+    );
+    compare("eight-bit", message.eight_bit(208), ColorSupport::EightBit);
+    compare(
+        "true",
+        message.rgb(255, 128, 0).on_rgb(16, 16, 16),
+        ColorSupport::True,
+    );
+    compare(
+        "true to simple",
+        message.rgb(255, 128, 0).on_rgb(16, 16, 16),
+        ColorSupport::Simple,
+    );
+    compare("number", 1234.green().italic(), ColorSupport::Simple);
+    println!();
+    compare_paint(
+        "paint simple",
+        Spec::new().red().bold(),
+        ColorSupport::Simple,
+    );
+    compare_paint(
+        "paint true",
+        Spec::new().rgb(255, 128, 0).on_rgb(16, 16, 16),
+        ColorSupport::True,
+    );
+    compare_paint(
+        "paint true to simple",
+        Spec::new().rgb(255, 128, 0).on_rgb(16, 16, 16),
+        ColorSupport::Simple,
+    );
+}
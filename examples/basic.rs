@@ -1,6 +1,6 @@
 // Importing * from prelude magically adds the coloring methods to strings.
-use chromaterm::Colors;
 use chromaterm::prelude::*;
+use chromaterm::{Colors, Role};
 use rand::prelude::*;
 
 fn main() {
@@ -28,7 +28,7 @@ fn main() {
             .italic()
     );
     println!(
-        "It works on all types that implement `Deref<Target=str>`, like {}",
+        "It works on owned strings, like {}",
         String::from("String")
             .underline()
             .bright_yellow()
@@ -60,4 +60,16 @@ fn main() {
     };
 
     println!("[{}]: It works!", status.color(color));
+
+    println!(
+        "----------------------------------------------------------------------------------------"
+    );
+
+    println!("Or give text a semantic role, and let the theme pick the colors.");
+    let role = match log_level {
+        LogLevel::Info => Role::Info,
+        LogLevel::Warn => Role::Warn,
+        LogLevel::Error => Role::Error,
+    };
+    println!("[{}]: It works!", status.role(role));
 }
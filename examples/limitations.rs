@@ -7,4 +7,7 @@ fn main() {
         "{} will be red",
         red_green.display_exact(ColorSupport::True)
     );
+
+    let green = ".styled().red().green()".styled().red().green();
+    println!("{} will be green", green.display_exact(ColorSupport::True));
 }
@@ -0,0 +1,201 @@
+use super::{Class, Scanner, sgr, sgr_params};
+use crate::ColorSupport;
+use crate::display::FmtFn;
+use std::fmt;
+use std::io;
+
+/// Wraps a writer, and converts the colors in the escape sequences written to it to
+/// colors that are supported.
+///
+/// This is useful for passing through the output of other programs. 8-bit and true
+/// colors in SGR sequences are converted to the closest supported color, and
+/// everything else is written as is. If there is no color support, SGR sequences are
+/// removed completely, but other escape sequences, like hyperlinks, are kept. Escape
+/// sequences that are split across multiple writes are handled correctly.
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::ColorSupport;
+/// use chromaterm::ansi::DowngradeWriter;
+/// use std::io::Write;
+///
+/// let mut writer = DowngradeWriter::new(Vec::new(), ColorSupport::Simple);
+/// writer.write_all(b"\x1B[1;38;2;25").unwrap();
+/// writer.write_all(b"5;0;0merror\x1B[0m").unwrap();
+/// assert_eq!(writer.into_inner(), b"\x1B[1;91merror\x1B[0m");
+/// ```
+#[derive(Debug)]
+pub struct DowngradeWriter<W: io::Write> {
+    inner: W,
+    supported: ColorSupport,
+    scanner: Scanner,
+    /// The escape sequence that is being read.
+    sequence: Vec<u8>,
+    /// If the escape sequence that is being read was too long to buffer, and is being
+    /// written as is.
+    is_passing: bool,
+}
+
+impl<W: io::Write> DowngradeWriter<W> {
+    /// Escape sequences that are longer than this are written as is.
+    const MAX_SEQUENCE_LEN: usize = 4096;
+
+    /// Wraps the writer.
+    pub fn new(inner: W, supported: ColorSupport) -> Self {
+        Self {
+            inner,
+            supported,
+            scanner: Scanner::new(),
+            sequence: Vec::new(),
+            is_passing: false,
+        }
+    }
+
+    /// Gets a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the wrapped writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps the writer. An unfinished escape sequence is discarded.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Writes a finished escape sequence, converting it if it's an SGR sequence.
+    fn write_sequence(&mut self) -> io::Result<()> {
+        let params = std::str::from_utf8(&self.sequence)
+            .ok()
+            .and_then(sgr_params);
+        match params {
+            Some(_) if self.supported == ColorSupport::None => Ok(()),
+            Some(params) => {
+                let supported = self.supported;
+                let params =
+                    FmtFn(|f: &mut fmt::Formatter<'_>| sgr::fmt_downgraded(params, supported, f));
+                write!(self.inner, "\x1B[{params}m")
+            }
+            None => self.inner.write_all(&self.sequence),
+        }
+    }
+}
+
+impl<W: io::Write> io::Write for DowngradeWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut run_start = None;
+        for (index, &byte) in buf.iter().enumerate() {
+            let class = self.scanner.feed(byte);
+            if class == Class::Text {
+                if !self.sequence.is_empty() {
+                    // NOTE An unfinished sequence was ended by text, so it's written as
+                    //      is, before the text.
+                    self.inner.write_all(&self.sequence)?;
+                    self.sequence.clear();
+                }
+                self.is_passing = false;
+                run_start.get_or_insert(index);
+                continue;
+            }
+            if let Some(run) = run_start.take() {
+                self.inner.write_all(&buf[run..index])?;
+            }
+            if self.scanner.is_starting() {
+                // NOTE An unfinished sequence was interrupted by a new one, so it's
+                //      written as is, like a terminal would receive it.
+                self.inner.write_all(&self.sequence)?;
+                self.sequence.clear();
+                self.is_passing = false;
+            }
+            if self.is_passing {
+                self.inner.write_all(&[byte])?;
+            } else if self.sequence.len() < Self::MAX_SEQUENCE_LEN {
+                self.sequence.push(byte);
+            } else {
+                self.inner.write_all(&self.sequence)?;
+                self.inner.write_all(&[byte])?;
+                self.sequence.clear();
+                self.is_passing = true;
+            }
+            if class == Class::End {
+                if !self.is_passing {
+                    self.write_sequence()?;
+                }
+                self.sequence.clear();
+                self.is_passing = false;
+            }
+        }
+        if let Some(run) = run_start {
+            self.inner.write_all(&buf[run..])?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use std::io::Write;
+
+    /// Helper to downgrade text in a single write.
+    fn downgrade(text: &str, supported: ColorSupport) -> String {
+        let mut writer = DowngradeWriter::new(Vec::new(), supported);
+        writer.write_all(text.as_bytes()).unwrap();
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    #[rstest]
+    #[case("plain", ColorSupport::None, "plain")]
+    #[case(
+        "\x1B[38;2;0;0;255mx\x1B[0m",
+        ColorSupport::True,
+        "\x1B[38;2;0;0;255mx\x1B[0m"
+    )]
+    #[case(
+        "\x1B[38;2;0;0;255mx\x1B[0m",
+        ColorSupport::EightBit,
+        "\x1B[38;5;21mx\x1B[0m"
+    )]
+    #[case("\x1B[48;5;208mx\x1B[49m", ColorSupport::Simple, "\x1B[103mx\x1B[49m")]
+    #[case("\x1B[1;31mx\x1B[0m", ColorSupport::None, "x")]
+    #[case("\x1B]8;;url\x1B\\\x1B[31mx", ColorSupport::None, "\x1B]8;;url\x1B\\x")]
+    #[case("\x1B[2K\x1B[1Ax", ColorSupport::Simple, "\x1B[2K\x1B[1Ax")]
+    #[case("\x1B[3\x1B[32mx", ColorSupport::Simple, "\x1B[3\x1B[32mx")]
+    #[case("\x1B[3\nabc\x1B[0m", ColorSupport::Simple, "\x1B[3\nabc\x1B[0m")]
+    fn test_downgrade(#[case] text: &str, #[case] supported: ColorSupport, #[case] expected: &str) {
+        assert_eq!(downgrade(text, supported), expected);
+    }
+
+    #[test]
+    fn test_split_sequences() {
+        let input = "\x1B[1;38;2;255;0;0merror\x1B[0m \x1B]8;;url\x1B\\caf\u{E9}\x1B]8;;\x1B\\";
+        let expected = downgrade(input, ColorSupport::EightBit);
+        for size in 1..input.len() {
+            let mut writer = DowngradeWriter::new(Vec::new(), ColorSupport::EightBit);
+            for chunk in input.as_bytes().chunks(size) {
+                writer.write_all(chunk).unwrap();
+            }
+            assert_eq!(
+                String::from_utf8(writer.into_inner()).unwrap(),
+                expected,
+                "chunk size {size}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_long_sequence() {
+        let title = "x".repeat(5000);
+        let input = format!("\x1B]0;{title}\x07text");
+        assert_eq!(downgrade(&input, ColorSupport::Simple), input);
+    }
+}
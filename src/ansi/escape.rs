@@ -0,0 +1,112 @@
+/// Classifies each byte of a text stream as either text or part of an escape sequence.
+///
+/// This works on bytes instead of characters, because all escape sequences are ASCII,
+/// and streams may be split in the middle of a character. Bytes that are part of a
+/// multibyte UTF-8 character are always text, unless they're inside of a string
+/// sequence, like an OSC.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub(crate) struct Scanner {
+    state: State,
+}
+
+/// The state of the scanner.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+enum State {
+    /// Not in an escape sequence.
+    #[default]
+    Text,
+    /// Just read `ESC`.
+    Escape,
+    /// Read `ESC` and intermediate bytes, like `ESC (`.
+    Intermediate,
+    /// In a control sequence (CSI), which ends with a byte in `@..=~`.
+    Csi,
+    /// In a string sequence (OSC, DCS, SOS, PM, or APC), which ends with `BEL` or
+    /// `ESC \`.
+    String,
+    /// Read `ESC` while in a string sequence.
+    StringEscape,
+}
+
+/// The class of a byte.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub(crate) enum Class {
+    /// The byte is text.
+    Text,
+    /// The byte is part of an escape sequence, and the sequence hasn't ended.
+    Escape,
+    /// The byte is the last byte of an escape sequence.
+    End,
+}
+
+impl Scanner {
+    const ESC: u8 = 0x1B;
+    const BEL: u8 = 0x07;
+
+    /// Creates a scanner that starts outside of any escape sequence.
+    #[inline]
+    pub(crate) const fn new() -> Self {
+        Self { state: State::Text }
+    }
+
+    /// Checks if the last byte started a new escape sequence.
+    #[inline]
+    pub(crate) fn is_starting(&self) -> bool {
+        self.state == State::Escape
+    }
+
+    /// Classifies the next byte.
+    pub(crate) fn feed(&mut self, byte: u8) -> Class {
+        let (state, class) = match (self.state, byte) {
+            (State::Text, Self::ESC) => (State::Escape, Class::Escape),
+            (State::Text, _) => (State::Text, Class::Text),
+            (State::Escape, b'[') => (State::Csi, Class::Escape),
+            (State::Escape, b']' | b'P' | b'X' | b'^' | b'_') => (State::String, Class::Escape),
+            (State::Escape | State::Intermediate, 0x20..=0x2F) => {
+                (State::Intermediate, Class::Escape)
+            }
+            (State::Escape | State::Intermediate, 0x30..=0x7E) => (State::Text, Class::End),
+            (State::Csi, 0x20..=0x3F) => (State::Csi, Class::Escape),
+            (State::Csi, 0x40..=0x7E) => (State::Text, Class::End),
+            // NOTE Malformed sequences are ended, and the unexpected byte is handled
+            //      like it would be outside of an escape sequence.
+            (State::Escape | State::Intermediate | State::Csi, _) => {
+                self.state = State::Text;
+                return self.feed(byte);
+            }
+            (State::String, Self::BEL) => (State::Text, Class::End),
+            (State::String, Self::ESC) => (State::StringEscape, Class::Escape),
+            (State::String, _) => (State::String, Class::Escape),
+            (State::StringEscape, b'\\') => (State::Text, Class::End),
+            (State::StringEscape, _) => (State::String, Class::Escape),
+        };
+        self.state = state;
+        class
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("plain", "ttttt")]
+    #[case("\x1B[1;31mx", "eeeeeeet")]
+    #[case("\x1B]8;;url\x1B\\x", "eeeeeeeeeet")]
+    #[case("\x1B]0;title\x07x", "eeeeeeeeeet")]
+    #[case("\x1B(Bx", "eeet")]
+    #[case("\x1B7x", "eet")]
+    #[case("\x1B[1\nx", "eeett")]
+    fn test_feed(#[case] input: &str, #[case] expected: &str) {
+        let mut scanner = Scanner::new();
+        let classes: String = input
+            .bytes()
+            .map(|byte| match scanner.feed(byte) {
+                Class::Text => 't',
+                Class::Escape | Class::End => 'e',
+            })
+            .collect();
+        assert_eq!(classes, expected);
+    }
+}
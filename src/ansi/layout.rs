@@ -0,0 +1,337 @@
+use super::{State, Token, tokens, visible_width};
+use crate::display::Padding;
+use std::borrow::Cow;
+use std::fmt;
+use unicode_width::UnicodeWidthChar;
+
+/// Truncates the text to at most `width` columns, ending it with the `ellipsis` if
+/// anything was cut off. The ellipsis is left out if it doesn't fit.
+///
+/// Escape sequences are kept, and any colors, styles, or hyperlinks that are still
+/// active at the cut are closed, so that the truncated text never leaks its styling.
+/// The ellipsis is styled like the text that was cut off.
+///
+/// This doesn't allocate if the text already fits.
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::ansi::truncate;
+///
+/// let text = "\x1B[38;2;255;0;0mlong red text\x1B[0m";
+/// assert_eq!(truncate(text, 7, Some("...")), "\x1B[38;2;255;0;0mlong...\x1B[0m");
+/// assert_eq!(truncate(text, 4, None), "\x1B[38;2;255;0;0mlong\x1B[0m");
+/// assert_eq!(truncate(text, 20, Some("...")), text);
+/// ```
+pub fn truncate<'a>(text: &'a str, width: usize, ellipsis: Option<&str>) -> Cow<'a, str> {
+    if visible_width(text) <= width {
+        return Cow::Borrowed(text);
+    }
+    let ellipsis = ellipsis.unwrap_or_default();
+    let ellipsis_width = visible_width(ellipsis);
+    let (ellipsis, width) = if ellipsis_width <= width {
+        (ellipsis, width - ellipsis_width)
+    } else {
+        ("", width)
+    };
+    let mut truncated = String::with_capacity(text.len());
+    let mut state = State::default();
+    // NOTE Escape sequences are only kept if they're followed by text that fits, or
+    //      by the ellipsis, so that nothing is opened right before the cut.
+    let mut pending = String::new();
+    let mut pending_state = State::default();
+    let mut used = 0;
+    for token in tokens(text) {
+        match token {
+            Token::Escape(sequence) => {
+                pending_state.apply(sequence);
+                pending.push_str(sequence);
+            }
+            Token::Text(run) => {
+                let cut = run.char_indices().find_map(|(index, c)| {
+                    used += c.width().unwrap_or(0);
+                    (used > width).then_some(index)
+                });
+                if cut != Some(0) || !ellipsis.is_empty() {
+                    truncated.push_str(&pending);
+                    pending.clear();
+                    state = pending_state;
+                }
+                match cut {
+                    Some(index) => {
+                        truncated.push_str(&run[..index]);
+                        break;
+                    }
+                    None => truncated.push_str(run),
+                }
+            }
+        }
+    }
+    truncated.push_str(ellipsis);
+    push_close(&mut truncated, &state);
+    Cow::Owned(truncated)
+}
+
+/// Pads the text with spaces to at least `width` columns. Escape sequences don't count
+/// towards the width, and the padding is written outside of them.
+///
+/// This doesn't allocate if the text is already wide enough.
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::ansi::pad;
+/// use std::fmt::Alignment;
+///
+/// let text = "\x1B[31mok\x1B[0m";
+/// assert_eq!(pad(text, 4, Alignment::Right), "  \x1B[31mok\x1B[0m");
+/// assert_eq!(pad(text, 5, Alignment::Center), " \x1B[31mok\x1B[0m  ");
+/// ```
+pub fn pad(text: &str, width: usize, alignment: fmt::Alignment) -> Cow<'_, str> {
+    let padding = width.saturating_sub(visible_width(text));
+    if padding == 0 {
+        return Cow::Borrowed(text);
+    }
+    let mut padded = String::with_capacity(text.len() + padding);
+    let padding = Padding::new(padding, ' ', alignment);
+    // NOTE Writing to a `String` never fails.
+    let _ = padding.write_before(&mut padded);
+    padded.push_str(text);
+    let _ = padding.write_after(&mut padded);
+    Cow::Owned(padded)
+}
+
+/// Wraps the text into lines of at most `width` columns, breaking at whitespace.
+/// Words that are wider than a line are broken wherever they need to be. Existing
+/// line breaks are kept.
+///
+/// Each line is balanced: colors, styles, and hyperlinks that are active at the end of
+/// a line are closed, and reopened at the start of the next line.
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::ansi::wrap;
+///
+/// let lines = wrap("\x1B[1;31mone two\x1B[0m three", 7);
+/// assert_eq!(lines, ["\x1B[1;31mone two\x1B[0m", "three"]);
+/// let lines = wrap("\x1B[32mone two three\x1B[0m", 8);
+/// assert_eq!(lines, ["\x1B[32mone two\x1B[0m", "\x1B[32mthree\x1B[0m"]);
+/// ```
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut wrapper = Wrapper::new(width.max(1));
+    for token in tokens(text) {
+        match token {
+            Token::Escape(sequence) => wrapper.push_escape(sequence),
+            Token::Text(run) => run.chars().for_each(|c| wrapper.push_char(c)),
+        }
+    }
+    wrapper.finish()
+}
+
+/// Part of a word that hasn't been placed on a line yet.
+#[derive(Debug, Copy, Clone)]
+enum Piece<'a> {
+    Escape(&'a str),
+    Char(char, usize),
+}
+
+/// Greedily fills lines with words.
+struct Wrapper<'a> {
+    width: usize,
+    lines: Vec<String>,
+    /// The line that is being filled.
+    line: String,
+    line_width: usize,
+    /// The state at the end of the line that is being filled.
+    state: State<'a>,
+    /// Whitespace after the last word, which is dropped if the line is broken.
+    space: String,
+    space_width: usize,
+    /// The word that is being read.
+    word: Vec<Piece<'a>>,
+    word_width: usize,
+}
+
+impl<'a> Wrapper<'a> {
+    fn new(width: usize) -> Self {
+        Self {
+            width,
+            lines: Vec::new(),
+            line: String::new(),
+            line_width: 0,
+            state: State::default(),
+            space: String::new(),
+            space_width: 0,
+            word: Vec::new(),
+            word_width: 0,
+        }
+    }
+
+    fn push_escape(&mut self, sequence: &'a str) {
+        self.word.push(Piece::Escape(sequence));
+    }
+
+    fn push_char(&mut self, c: char) {
+        match c {
+            '\n' => {
+                self.place_word();
+                self.clear_space();
+                self.break_line();
+            }
+            c if c.is_whitespace() => {
+                self.place_word();
+                self.space.push(c);
+                self.space_width += c.width().unwrap_or(1);
+            }
+            c => {
+                let width = c.width().unwrap_or(0);
+                self.word.push(Piece::Char(c, width));
+                self.word_width += width;
+            }
+        }
+    }
+
+    /// Places the word that was read on the current line, or on the next line if it
+    /// doesn't fit.
+    fn place_word(&mut self) {
+        if self.word.is_empty() {
+            return;
+        }
+        let fits = self.line_width + self.space_width + self.word_width <= self.width;
+        if self.word_width > 0 && self.line_width > 0 && !fits {
+            self.clear_space();
+            self.break_line();
+        }
+        self.line.push_str(&self.space);
+        self.line_width += self.space_width;
+        self.clear_space();
+        for piece in std::mem::take(&mut self.word) {
+            match piece {
+                Piece::Escape(sequence) => {
+                    self.state.apply(sequence);
+                    self.line.push_str(sequence);
+                }
+                Piece::Char(c, width) => {
+                    if self.line_width > 0 && self.line_width + width > self.width {
+                        self.break_line();
+                    }
+                    self.line.push(c);
+                    self.line_width += width;
+                }
+            }
+        }
+        self.word_width = 0;
+    }
+
+    fn clear_space(&mut self) {
+        self.space.clear();
+        self.space_width = 0;
+    }
+
+    /// Closes the current line, and starts the next one.
+    fn break_line(&mut self) {
+        push_close(&mut self.line, &self.state);
+        let mut line = String::new();
+        push_open(&mut line, &self.state);
+        self.lines.push(std::mem::replace(&mut self.line, line));
+        self.line_width = 0;
+    }
+
+    fn finish(mut self) -> Vec<String> {
+        self.place_word();
+        push_close(&mut self.line, &self.state);
+        self.lines.push(self.line);
+        self.lines
+    }
+}
+
+/// Writes the sequences that open everything in the state.
+#[inline]
+fn push_open(s: &mut String, state: &State<'_>) {
+    // NOTE Writing to a String never fails.
+    let _ = state.write_open(s);
+}
+
+/// Writes the sequences that close everything in the state.
+#[inline]
+fn push_close(s: &mut String, state: &State<'_>) {
+    // NOTE Writing to a String never fails.
+    let _ = state.write_close(s);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("plain text", 5, None, "plain")]
+    #[case("plain text", 8, Some("..."), "plain...")]
+    #[case("plain", 2, Some("..."), "pl")]
+    #[case("\x1B[1mbold\x1B[0m text", 6, None, "\x1B[1mbold\x1B[0m t")]
+    #[case("\x1B[1mbold text", 3, None, "\x1B[1mbol\x1B[0m")]
+    #[case("\x1B[31m\u{4F60}\u{597D}", 3, None, "\x1B[31m\u{4F60}\x1B[0m")]
+    #[case(
+        "\x1B]8;;https://example.com\x1B\\a link\x1B]8;;\x1B\\",
+        1,
+        None,
+        "\x1B]8;;https://example.com\x1B\\a\x1B]8;;\x1B\\"
+    )]
+    #[case("\x1B[31mab\x1B[0m\x1B[32mcd\x1B[0m", 2, None, "\x1B[31mab\x1B[0m")]
+    #[case("\x1B[1mab\x1B[0mcd", 2, Some(""), "\x1B[1mab\x1B[0m")]
+    #[case(
+        "\x1B[31mab\x1B[32mcd\x1B[0m",
+        3,
+        Some("\u{2026}"),
+        "\x1B[31mab\x1B[32m\u{2026}\x1B[0m"
+    )]
+    #[case("\x1B[1mab\x1B[0mcd", 3, Some("\u{2026}"), "\x1B[1mab\x1B[0m\u{2026}")]
+    fn test_truncate(
+        #[case] text: &str,
+        #[case] width: usize,
+        #[case] ellipsis: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(truncate(text, width, ellipsis), expected);
+    }
+
+    #[rstest]
+    #[case("ok", 1, fmt::Alignment::Left, "ok")]
+    #[case("ok", 4, fmt::Alignment::Left, "ok  ")]
+    #[case("\u{4F60}", 4, fmt::Alignment::Right, "  \u{4F60}")]
+    fn test_pad(
+        #[case] text: &str,
+        #[case] width: usize,
+        #[case] alignment: fmt::Alignment,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(pad(text, width, alignment), expected);
+    }
+
+    #[rstest]
+    #[case("", 5, &[""])]
+    #[case("one two three", 7, &["one two", "three"])]
+    #[case("one  two", 3, &["one", "two"])]
+    #[case("one\n\ntwo", 10, &["one", "", "two"])]
+    #[case("abcdefgh", 3, &["abc", "def", "gh"])]
+    #[case(
+        "\x1B[4mone \x1B[1mtwo\x1B[22m three\x1B[0m",
+        5,
+        &["\x1B[4mone\x1B[0m", "\x1B[4m\x1B[1mtwo\x1B[22m\x1B[0m", "\x1B[4mthree\x1B[0m"],
+    )]
+    #[case(
+        "\x1B[38;2;1;2;3mabcd\x1B[0m",
+        2,
+        &["\x1B[38;2;1;2;3mab\x1B[0m", "\x1B[38;2;1;2;3mcd\x1B[0m"],
+    )]
+    #[case(
+        "\x1B]8;;url\x07a b\x1B]8;;\x07",
+        1,
+        &["\x1B]8;;url\x07a\x1B]8;;\x1B\\", "\x1B]8;;url\x07b\x1B]8;;\x07"],
+    )]
+    #[case("\u{4F60}\u{597D}\u{5417}", 4, &["\u{4F60}\u{597D}", "\u{5417}"])]
+    fn test_wrap(#[case] text: &str, #[case] width: usize, #[case] expected: &[&str]) {
+        assert_eq!(wrap(text, width), expected);
+    }
+}
@@ -0,0 +1,162 @@
+//! Tools for working with text that already contains ANSI escape sequences.
+pub use downgrade::DowngradeWriter;
+pub(crate) use escape::{Class, Scanner};
+pub use layout::{pad, truncate, wrap};
+pub use parse::{Spans, Unknown, parse};
+pub(crate) use parse::{Token, link_url, sgr_params, tokens};
+pub(crate) use state::State;
+use std::borrow::Cow;
+use std::io;
+pub(crate) use width::WidthCounter;
+pub use width::visible_width;
+
+mod downgrade;
+mod escape;
+mod layout;
+mod parse;
+pub(crate) mod sgr;
+mod state;
+mod width;
+
+/// Removes all escape sequences from the text, including colors, styles, OSC 8
+/// hyperlinks, and window titles.
+///
+/// This doesn't allocate if there's nothing to remove.
+///
+/// # Example
+///
+/// ```rust
+/// use std::borrow::Cow;
+///
+/// let text = "\x1B[1;31merror\x1B[0m: \x1B]8;;https://example.com\x1B\\link\x1B]8;;\x1B\\";
+/// assert_eq!(chromaterm::strip(text), "error: link");
+/// assert!(matches!(chromaterm::strip("plain"), Cow::Borrowed("plain")));
+/// ```
+pub fn strip(text: &str) -> Cow<'_, str> {
+    let Some(start) = text.find('\x1B') else {
+        return Cow::Borrowed(text);
+    };
+    let mut stripped = String::with_capacity(text.len());
+    stripped.push_str(&text[..start]);
+    let mut scanner = Scanner::new();
+    let mut run_start = None;
+    for (index, byte) in text.bytes().enumerate().skip(start) {
+        match (scanner.feed(byte), run_start) {
+            (Class::Text, None) => run_start = Some(index),
+            (Class::Text, Some(_)) => {}
+            (Class::Escape | Class::End, Some(run)) => {
+                stripped.push_str(&text[run..index]);
+                run_start = None;
+            }
+            (Class::Escape | Class::End, None) => {}
+        }
+    }
+    if let Some(run) = run_start {
+        stripped.push_str(&text[run..]);
+    }
+    Cow::Owned(stripped)
+}
+
+/// Wraps a writer, and removes all escape sequences from the bytes written to it.
+/// Escape sequences that are split across multiple writes are handled correctly.
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::ansi::StripWriter;
+/// use std::io::Write;
+///
+/// let mut writer = StripWriter::new(Vec::new());
+/// writer.write_all(b"\x1B[3").unwrap();
+/// writer.write_all(b"1mred\x1B[0m").unwrap();
+/// assert_eq!(writer.into_inner(), b"red");
+/// ```
+#[derive(Debug)]
+pub struct StripWriter<W: io::Write> {
+    inner: W,
+    scanner: Scanner,
+}
+
+impl<W: io::Write> StripWriter<W> {
+    /// Wraps the writer.
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            scanner: Scanner::new(),
+        }
+    }
+
+    /// Gets a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the wrapped writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps the writer. An unfinished escape sequence is discarded.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: io::Write> io::Write for StripWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut run_start = None;
+        for (index, &byte) in buf.iter().enumerate() {
+            match (self.scanner.feed(byte), run_start) {
+                (Class::Text, None) => run_start = Some(index),
+                (Class::Text, Some(_)) => {}
+                (Class::Escape | Class::End, Some(run)) => {
+                    self.inner.write_all(&buf[run..index])?;
+                    run_start = None;
+                }
+                (Class::Escape | Class::End, None) => {}
+            }
+        }
+        if let Some(run) = run_start {
+            self.inner.write_all(&buf[run..])?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use std::io::Write;
+
+    #[rstest]
+    #[case("", "")]
+    #[case("plain text", "plain text")]
+    #[case("\x1B[31mred\x1B[0m", "red")]
+    #[case("a\x1B[38;2;1;2;3mb\x1B[0mc", "abc")]
+    #[case("\x1B]8;;https://example.com\x1B\\link\x1B]8;;\x1B\\", "link")]
+    #[case("\x1B]0;title\x07text", "text")]
+    #[case("\x1BP1$r0m\x1B\\text", "text")]
+    #[case("\x1B(Bcharset", "charset")]
+    #[case("caf\u{E9} \x1B[1m\u{1F600}\x1B[0m", "caf\u{E9} \u{1F600}")]
+    #[case("unfinished \x1B[31", "unfinished ")]
+    fn test_strip(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(strip(input), expected);
+    }
+
+    #[test]
+    fn test_strip_writer_split_sequences() {
+        let input = "\x1B[1;31merror\x1B[0m \x1B]8;;url\x1B\\link\x1B]8;;\x1B\\";
+        for size in 1..input.len() {
+            let mut writer = StripWriter::new(Vec::new());
+            for chunk in input.as_bytes().chunks(size) {
+                writer.write_all(chunk).unwrap();
+            }
+            assert_eq!(writer.into_inner(), b"error link", "chunk size {size}");
+        }
+    }
+}
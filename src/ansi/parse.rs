@@ -0,0 +1,229 @@
+use super::{Class, Scanner, sgr};
+use crate::Spec;
+
+/// Parses text containing SGR escape sequences (like `ESC [ 1;31 m`) into spans of text
+/// with the [`Spec`] that applies to them.
+///
+/// The parser is lenient: other escape sequences, like cursor movements or OSC 8
+/// hyperlinks, are skipped, malformed sequences are skipped, and parameters that
+/// aren't understood are ignored and reported with [`Spans::unknown`].
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::Spec;
+///
+/// let text = "\x1B[1;31merror\x1B[22m: \x1B[38;5;208mhot\x1B[0m";
+/// let spans: Vec<_> = chromaterm::ansi::parse(text).collect();
+/// assert_eq!(
+///     spans,
+///     [
+///         (Spec::new().bold().red(), "error"),
+///         (Spec::new().red(), ": "),
+///         (Spec::new().eight_bit(208), "hot"),
+///     ],
+/// );
+/// ```
+pub fn parse(text: &str) -> Spans<'_> {
+    Spans {
+        text,
+        tokens: tokens(text),
+        spec: Spec::new(),
+        unknown: Vec::new(),
+    }
+}
+
+/// Splits text into runs of text and escape sequences.
+#[inline]
+pub(crate) fn tokens(text: &str) -> Tokens<'_> {
+    Tokens { rest: text }
+}
+
+/// Part of a text containing escape sequences.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub(crate) enum Token<'a> {
+    /// A run of text, without any escape sequences.
+    Text(&'a str),
+    /// A single escape sequence, which may be malformed or unfinished.
+    Escape(&'a str),
+}
+
+/// An iterator over the tokens of a text. See [`tokens`].
+#[derive(Debug, Clone)]
+pub(crate) struct Tokens<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let (token, len) = if self.rest.starts_with('\x1B') {
+            let len = sequence_len(self.rest);
+            (Token::Escape(&self.rest[..len]), len)
+        } else {
+            let len = self.rest.find('\x1B').unwrap_or(self.rest.len());
+            (Token::Text(&self.rest[..len]), len)
+        };
+        self.rest = &self.rest[len..];
+        Some(token)
+    }
+}
+
+/// Gets the parameters of an SGR sequence, like the `1;31` in `ESC [ 1;31 m`. Returns
+/// `None` if the sequence isn't an SGR sequence.
+pub(crate) fn sgr_params(sequence: &str) -> Option<&str> {
+    let params = sequence.strip_prefix("\x1B[")?.strip_suffix('m')?;
+    // NOTE Private sequences (like `ESC [ > 4;1 m`) and sequences with intermediate
+    //      bytes aren't SGR sequences.
+    params
+        .bytes()
+        .all(|byte| matches!(byte, b'0'..=b';'))
+        .then_some(params)
+}
+
+/// Gets the URL of an OSC 8 hyperlink sequence. The URL is empty if the sequence ends
+/// a hyperlink. Returns `None` if the sequence isn't a finished OSC 8 sequence.
+pub(crate) fn link_url(sequence: &str) -> Option<&str> {
+    let rest = sequence.strip_prefix("\x1B]8;")?;
+    let rest = rest
+        .strip_suffix('\x07')
+        .or_else(|| rest.strip_suffix("\x1B\\"))?;
+    rest.split_once(';').map(|(_, url)| url)
+}
+
+/// An iterator over the styled spans of a text. See [`parse`].
+#[derive(Debug, Clone)]
+pub struct Spans<'a> {
+    text: &'a str,
+    tokens: Tokens<'a>,
+    spec: Spec,
+    unknown: Vec<Unknown<'a>>,
+}
+
+impl<'a> Spans<'a> {
+    /// Gets the spec at the current position. After all spans have been parsed, this
+    /// is the spec that would apply to any following text.
+    #[inline]
+    pub fn spec(&self) -> Spec {
+        self.spec
+    }
+
+    /// Gets the SGR parameters that weren't understood so far.
+    #[inline]
+    pub fn unknown(&self) -> &[Unknown<'a>] {
+        &self.unknown
+    }
+
+    /// Applies the escape sequence, if it's an SGR sequence.
+    fn apply(&mut self, sequence: &'a str) {
+        let Some(params) = sgr_params(sequence) else {
+            return;
+        };
+        let text = self.text;
+        let unknown = &mut self.unknown;
+        self.spec = sgr::apply(self.spec, params, |parameter| {
+            unknown.push(Unknown {
+                parameter,
+                offset: parameter.as_ptr() as usize - text.as_ptr() as usize,
+            });
+        });
+    }
+}
+
+impl<'a> Iterator for Spans<'a> {
+    type Item = (Spec, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.tokens.next()? {
+                Token::Text(text) => return Some((self.spec, text)),
+                Token::Escape(sequence) => self.apply(sequence),
+            }
+        }
+    }
+}
+
+/// An SGR parameter that wasn't understood.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Unknown<'a> {
+    parameter: &'a str,
+    offset: usize,
+}
+
+impl<'a> Unknown<'a> {
+    /// Gets the parameter, including any arguments, like `58;5;1`.
+    #[inline]
+    pub fn parameter(&self) -> &'a str {
+        self.parameter
+    }
+
+    /// Gets the byte offset of the parameter in the parsed text.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+/// Gets the length of the escape sequence at the start of the text. Unfinished
+/// sequences take up the rest of the text.
+fn sequence_len(text: &str) -> usize {
+    let mut scanner = Scanner::new();
+    for (index, byte) in text.bytes().enumerate() {
+        match scanner.feed(byte) {
+            Class::End => return index + 1,
+            Class::Text => return index,
+            Class::Escape if index > 0 && scanner.is_starting() => return index,
+            Class::Escape => {}
+        }
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", &[])]
+    #[case("plain", &[(Spec::new(), "plain")])]
+    #[case("\x1B[31mred\x1B[m plain", &[(Spec::new().red(), "red"), (Spec::new(), " plain")])]
+    #[case("\x1B[1m\x1B[4mbold", &[(Spec::new().bold().underline(), "bold")])]
+    #[case("a\x1B[2Kb", &[(Spec::new(), "a"), (Spec::new(), "b")])]
+    #[case("\x1B[?25l\x1B[>4;1mtext", &[(Spec::new(), "text")])]
+    #[case(
+        "\x1B]8;;https://example.com\x1B\\\x1B[34mlink",
+        &[(Spec::new().blue(), "link")],
+    )]
+    #[case("\x1B[3\x1B[32mgreen", &[(Spec::new().green(), "green")])]
+    #[case("\x1B[3\u{E9}t\u{E9}", &[(Spec::new(), "\u{E9}t\u{E9}")])]
+    #[case("\x1B[1mbold \x1B[3", &[(Spec::new().bold(), "bold ")])]
+    fn test_parse(#[case] text: &str, #[case] expected: &[(Spec, &str)]) {
+        let spans: Vec<_> = parse(text).collect();
+        assert_eq!(spans, expected);
+    }
+
+    #[test]
+    fn test_parse_unknown() {
+        let text = "\x1B[1;53mover\x1B[58;5;1;31mred";
+        let mut spans = parse(text);
+        let collected: Vec<_> = spans.by_ref().collect();
+        assert_eq!(
+            collected,
+            [
+                (Spec::new().bold(), "over"),
+                (Spec::new().bold().red(), "red")
+            ],
+        );
+        let unknown: Vec<_> = spans
+            .unknown()
+            .iter()
+            .map(|unknown| (unknown.parameter(), unknown.offset()))
+            .collect();
+        assert_eq!(unknown, [("53", 4), ("58;5;1", 13)]);
+    }
+}
@@ -0,0 +1,262 @@
+use crate::colors::{EightBit, True};
+use crate::conversion::ToSimple;
+use crate::{Color, ColorSupport, Colors, Spec, Styles};
+use std::fmt;
+
+/// Applies the parameters of an SGR sequence (the `1;31` in `ESC [ 1;31 m`) to a spec.
+///
+/// Parameters that aren't understood, or that can't be represented by a [`Spec`], are
+/// passed to `unknown`, and otherwise ignored. For extended colors, the parameter
+/// passed to `unknown` includes the parameters that were consumed as its arguments.
+pub(crate) fn apply<'a, U: FnMut(&'a str)>(spec: Spec, params: &'a str, mut unknown: U) -> Spec {
+    let mut spec = spec;
+    let mut split = params.split(';');
+    while let Some(param) = split.next() {
+        if let Some((code, args)) = param.split_once(':') {
+            let updated = match code {
+                "4" => underline(spec, args),
+                "38" => extended(&mut args.split(':'), true).map(|color| spec.color(color)),
+                "48" => extended(&mut args.split(':'), true).map(|color| spec.on_color(color)),
+                _ => None,
+            };
+            match updated {
+                Some(updated) => spec = updated,
+                None => unknown(param),
+            }
+            continue;
+        }
+        let code = if param.is_empty() {
+            0
+        } else if let Ok(code) = param.parse::<u8>() {
+            code
+        } else {
+            unknown(param);
+            continue;
+        };
+        spec = match code {
+            0 => Spec::new(),
+            1 => spec.bold(),
+            2 => spec.dim(),
+            3 => spec.italic(),
+            4 | 21 => spec.remove_style(Styles::CurlyUnderline).underline(),
+            5 | 6 => spec.blink(),
+            7 => spec.reverse(),
+            8 => spec.hidden(),
+            9 => spec.strike(),
+            22 => spec.remove_style(Styles::Bold).remove_style(Styles::Dim),
+            23 => spec.remove_style(Styles::Italic),
+            24 => spec
+                .remove_style(Styles::Underline)
+                .remove_style(Styles::CurlyUnderline),
+            25 => spec.remove_style(Styles::Blink),
+            27 => spec.remove_style(Styles::Reverse),
+            28 => spec.remove_style(Styles::Hidden),
+            29 => spec.remove_style(Styles::Strike),
+            30..=37 => spec.color(simple(code - 30)),
+            39 => spec.clear_color(),
+            40..=47 => spec.on_color(simple(code - 40)),
+            49 => spec.clear_on_color(),
+            90..=97 => spec.color(simple(code - 90 + 8)),
+            100..=107 => spec.on_color(simple(code - 100 + 8)),
+            38 | 48 | 58 => {
+                // NOTE The arguments are separate parameters, so they need to be
+                //      consumed even if the color is invalid.
+                let start = offset(params, param);
+                let mut consumed = split.clone();
+                let color = extended(&mut consumed, false);
+                let end = match consumed.clone().next() {
+                    Some(next) => offset(params, next) - 1,
+                    None => params.len(),
+                };
+                split = consumed;
+                match (code, color) {
+                    (38, Some(color)) => spec.color(color),
+                    (48, Some(color)) => spec.on_color(color),
+                    _ => {
+                        unknown(&params[start..end]);
+                        spec
+                    }
+                }
+            }
+            _ => {
+                unknown(param);
+                spec
+            }
+        };
+    }
+    spec
+}
+
+/// Writes the parameters of an SGR sequence, with the extended text and background
+/// colors rewritten to colors that are supported, and all other parameters as they
+/// are.
+///
+/// Colors are removed if there is no color support, but the caller should remove the
+/// whole sequence instead.
+pub(crate) fn fmt_downgraded(
+    params: &str,
+    supported: ColorSupport,
+    f: &mut fmt::Formatter<'_>,
+) -> Result<(), fmt::Error> {
+    let mut separator = "";
+    let mut split = params.split(';');
+    while let Some(param) = split.next() {
+        let (code, color, original) = match param.split_once(':') {
+            Some((code @ ("38" | "48"), args)) => {
+                (code, extended(&mut args.split(':'), true), param)
+            }
+            None if matches!(param, "38" | "48") => {
+                // NOTE The arguments are separate parameters, so they are kept with
+                //      the color even if the color is invalid.
+                let start = offset(params, param);
+                let mut consumed = split.clone();
+                let color = extended(&mut consumed, false);
+                let end = match consumed.clone().next() {
+                    Some(next) => offset(params, next) - 1,
+                    None => params.len(),
+                };
+                split = consumed;
+                (param, color, &params[start..end])
+            }
+            _ => (param, None, param),
+        };
+        let Some(color) = color.filter(|color| color.exact(supported).is_none()) else {
+            write!(f, "{separator}{original}")?;
+            separator = ";";
+            continue;
+        };
+        if let Some(color) = color.fallback(supported) {
+            f.write_str(separator)?;
+            match code {
+                "38" => color.fmt_fg(f)?,
+                _ => color.fmt_bg(f)?,
+            }
+            separator = ";";
+        }
+    }
+    Ok(())
+}
+
+/// Gets one of the 16 simple colors by its index.
+#[inline]
+fn simple(index: u8) -> Colors {
+    Colors::Simple(EightBit::from(index).to_simple())
+}
+
+/// Gets the offset of `part` in `whole`. `part` must be a substring of `whole`.
+#[inline]
+fn offset(whole: &str, part: &str) -> usize {
+    part.as_ptr() as usize - whole.as_ptr() as usize
+}
+
+/// Parses the underline style from the arguments of `4:`.
+fn underline(spec: Spec, args: &str) -> Option<Spec> {
+    let spec = spec
+        .remove_style(Styles::Underline)
+        .remove_style(Styles::CurlyUnderline);
+    match args {
+        "0" => Some(spec),
+        "3" => Some(spec.curly_underline()),
+        // NOTE Double, dotted, and dashed underlines are shown as normal underlines.
+        "" | "1" | "2" | "4" | "5" => Some(spec.underline()),
+        _ => None,
+    }
+}
+
+/// Parses an extended color from the arguments following `38` or `48`, like `5;n` or
+/// `2;r;g;b`.
+///
+/// If `colon` is `true`, the arguments are colon-separated, and a true color may
+/// include a color space ID (`2:id:r:g:b`), and empty arguments mean `0`.
+fn extended<'a, I: Iterator<Item = &'a str> + Clone>(args: &mut I, colon: bool) -> Option<Colors> {
+    let number = |args: &mut I| {
+        let arg = args.next()?;
+        if colon && arg.is_empty() {
+            Some(0)
+        } else {
+            arg.parse::<u8>().ok()
+        }
+    };
+    match args.next()? {
+        "5" => number(args).map(Colors::new_eight_bit),
+        "2" => {
+            if colon && args.clone().count() == 4 {
+                args.next();
+            }
+            let r = number(args)?;
+            let g = number(args)?;
+            let b = number(args)?;
+            Some(Colors::True(True::from_rgb(r, g, b)))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::FmtFn;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", Spec::new())]
+    #[case("0", Spec::new())]
+    #[case("1;31", Spec::new().bold().red())]
+    #[case("01;04", Spec::new().bold().underline())]
+    #[case("1;22", Spec::new())]
+    #[case("3;9;23", Spec::new().strike())]
+    #[case("91;102", Spec::new().bright_red().on_bright_green())]
+    #[case("31;39;44;49", Spec::new())]
+    #[case("38;5;208", Spec::new().eight_bit(208))]
+    #[case("48;2;1;2;3", Spec::new().on_rgb(1, 2, 3))]
+    #[case("38:5:208", Spec::new().eight_bit(208))]
+    #[case("38:2:1:2:3", Spec::new().rgb(1, 2, 3))]
+    #[case("38:2::1:2:3", Spec::new().rgb(1, 2, 3))]
+    #[case("48:2:0:1:2:3", Spec::new().on_rgb(1, 2, 3))]
+    #[case("4:3", Spec::new().curly_underline())]
+    #[case("4:3;4", Spec::new().underline())]
+    #[case("4;4:0", Spec::new())]
+    #[case("1;31;0;32", Spec::new().green())]
+    fn test_apply(#[case] params: &str, #[case] expected: Spec) {
+        let mut unknown = Vec::new();
+        assert_eq!(apply(Spec::new(), params, |p| unknown.push(p)), expected);
+        assert!(unknown.is_empty(), "unknown: {unknown:?}");
+    }
+
+    #[rstest]
+    #[case("1;53;31", Spec::new().bold().red(), &["53"])]
+    #[case("38;5;300;1", Spec::new().bold(), &["38;5;300"])]
+    #[case("38;2;1;2", Spec::new(), &["38;2;1;2"])]
+    #[case("58;5;1;3", Spec::new().italic(), &["58;5;1"])]
+    #[case("38;7;1", Spec::new().bold(), &["38;7"])]
+    #[case("x;4:9;2", Spec::new().dim(), &["x", "4:9"])]
+    fn test_apply_unknown(
+        #[case] params: &str,
+        #[case] expected: Spec,
+        #[case] expected_unknown: &[&str],
+    ) {
+        let mut unknown = Vec::new();
+        assert_eq!(apply(Spec::new(), params, |p| unknown.push(p)), expected);
+        assert_eq!(unknown, expected_unknown);
+    }
+
+    #[rstest]
+    #[case("1;38;2;255;0;0;4", ColorSupport::Simple, "1;91;4")]
+    #[case("38;2;255;0;0", ColorSupport::EightBit, "38;5;196")]
+    #[case("48:2::255:0:0", ColorSupport::EightBit, "48;5;196")]
+    #[case("38;5;208;48;5;1", ColorSupport::Simple, "93;41")]
+    #[case("38;5;208", ColorSupport::EightBit, "38;5;208")]
+    #[case("38:2::1:2:3", ColorSupport::True, "38:2::1:2:3")]
+    #[case("38;5;300;1", ColorSupport::Simple, "38;5;300;1")]
+    #[case("58;2;1;2;3;4:3", ColorSupport::Simple, "58;2;1;2;3;4:3")]
+    #[case("", ColorSupport::Simple, "")]
+    #[case("1;38;5;1;4", ColorSupport::None, "1;4")]
+    fn test_fmt_downgraded(
+        #[case] params: &str,
+        #[case] supported: ColorSupport,
+        #[case] expected: &str,
+    ) {
+        let downgraded = FmtFn(|f: &mut fmt::Formatter<'_>| fmt_downgraded(params, supported, f));
+        assert_eq!(downgraded.to_string(), expected);
+    }
+}
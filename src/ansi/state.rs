@@ -0,0 +1,48 @@
+use super::{link_url, sgr, sgr_params};
+use crate::Spec;
+use crate::display::FmtFn;
+use std::fmt::{self, Write};
+
+/// The colors, styles, and hyperlink that are active at some point in a text.
+///
+/// This is used to close everything that's open before the text is cut, and to reopen
+/// it where the text continues.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub(crate) struct State<'a> {
+    /// The active colors and styles.
+    spec: Spec,
+    /// The sequence that opened the active hyperlink.
+    link: Option<&'a str>,
+}
+
+impl<'a> State<'a> {
+    /// Updates the state with an escape sequence. Sequences that don't change colors,
+    /// styles, or hyperlinks are ignored.
+    pub(crate) fn apply(&mut self, sequence: &'a str) {
+        if let Some(params) = sgr_params(sequence) {
+            self.spec = sgr::apply(self.spec, params, |_| {});
+        } else if let Some(url) = link_url(sequence) {
+            self.link = (!url.is_empty()).then_some(sequence);
+        }
+    }
+
+    /// Writes the sequences that make everything in this state active.
+    pub(crate) fn write_open<W: Write>(&self, w: &mut W) -> Result<(), fmt::Error> {
+        if let Some(link) = self.link {
+            w.write_str(link)?;
+        }
+        let spec = FmtFn(|f: &mut fmt::Formatter<'_>| self.spec.fmt_raw(f).map(|_| ()));
+        write!(w, "{spec}")
+    }
+
+    /// Writes the sequences that end everything in this state.
+    pub(crate) fn write_close<W: Write>(&self, w: &mut W) -> Result<(), fmt::Error> {
+        if !self.spec.is_empty() {
+            w.write_str("\x1B[0m")?;
+        }
+        if self.link.is_some() {
+            w.write_str("\x1B]8;;\x1B\\")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use super::{Class, Scanner};
+use std::fmt;
+use unicode_width::UnicodeWidthStr;
+
+/// Gets the number of terminal columns the text takes up. Escape sequences take up no
+/// columns, East Asian wide characters and emoji take up 2 columns, and combining marks
+/// and other zero-width characters take up none.
+///
+/// # Example
+///
+/// ```rust
+/// assert_eq!(chromaterm::ansi::visible_width("\x1B[31mred\x1B[0m"), 3);
+/// assert_eq!(chromaterm::ansi::visible_width("\x1B[1m\u{4F60}\u{597D}\x1B[0m"), 4);
+/// assert_eq!(chromaterm::ansi::visible_width("e\u{301}"), 1);
+/// ```
+pub fn visible_width(text: &str) -> usize {
+    let mut counter = WidthCounter::new();
+    counter.count(text);
+    counter.width()
+}
+
+/// Counts the columns of written text, skipping escape sequences.
+///
+/// Text is measured in runs between escape sequences, so that multi-character
+/// sequences, like emoji with variation selectors, are measured together.
+#[derive(Debug, Default)]
+pub(crate) struct WidthCounter {
+    width: usize,
+    scanner: Scanner,
+}
+
+impl WidthCounter {
+    /// Creates a counter that hasn't counted anything.
+    #[inline]
+    pub(crate) const fn new() -> Self {
+        Self {
+            width: 0,
+            scanner: Scanner::new(),
+        }
+    }
+
+    /// Gets the counted width.
+    #[inline]
+    pub(crate) fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Counts the columns of the text.
+    pub(crate) fn count(&mut self, text: &str) {
+        let mut run_start = None;
+        for (index, byte) in text.bytes().enumerate() {
+            match (self.scanner.feed(byte), run_start) {
+                (Class::Text, None) => run_start = Some(index),
+                (Class::Text, Some(_)) => {}
+                (Class::Escape | Class::End, Some(run)) => {
+                    self.width += text[run..index].width();
+                    run_start = None;
+                }
+                (Class::Escape | Class::End, None) => {}
+            }
+        }
+        if let Some(run) = run_start {
+            self.width += text[run..].width();
+        }
+    }
+}
+
+impl fmt::Write for WidthCounter {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
+        self.count(s);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use std::fmt::Write;
+
+    #[rstest]
+    #[case("", 0)]
+    #[case("plain", 5)]
+    #[case("\x1B[1;31merror\x1B[0m", 5)]
+    #[case("\x1B]8;;https://example.com\x1B\\link\x1B]8;;\x1B\\", 4)]
+    #[case("caf\u{E9}", 4)]
+    #[case("cafe\u{301}", 4)]
+    #[case("\u{65E5}\u{672C}\u{8A9E}", 6)]
+    #[case("\u{FF21}\u{FF22}", 4)]
+    #[case("\x1B[32m\u{1F600}\x1B[0m!", 3)]
+    #[case("\u{2764}\u{FE0F}", 2)]
+    #[case("a\u{200B}b", 2)]
+    fn test_visible_width(#[case] text: &str, #[case] expected: usize) {
+        assert_eq!(visible_width(text), expected);
+    }
+
+    #[test]
+    fn test_counter_split_sequences() {
+        let mut counter = WidthCounter::new();
+        write!(counter, "\x1B[3").unwrap();
+        write!(counter, "1m\u{4F60}").unwrap();
+        write!(counter, "\x1B[0m").unwrap();
+        assert_eq!(counter.width(), 2);
+    }
+}
@@ -0,0 +1,365 @@
+//! Rendering colorized and styled values with custom backends.
+//!
+//! The wrappers in this crate describe *what* should be shown, and a [`Backend`] decides
+//! *how* to show it. Each wrapper opens its attributes before its value is written, and
+//! closes them afterwards, so a backend can write escape sequences, HTML, a markup
+//! language, or anything else. [`Ansi`] writes escape sequences,
+//! [`Html`](crate::html::Html) writes HTML, [`Prompt`](crate::prompt::Prompt) writes
+//! shell prompts, [`Tmux`](crate::tmux::Tmux) writes tmux format strings, and
+//! [`Mirc`](crate::mirc::Mirc) writes mIRC formatting codes.
+//!
+//! # Example
+//!
+//! ```rust
+//! use chromaterm::prelude::*;
+//! use chromaterm::backend::{Attr, Backend};
+//! use chromaterm::Styles;
+//! use std::fmt;
+//!
+//! /// Writes bold text as `*text*`, and ignores everything else.
+//! #[derive(Clone)]
+//! struct Markdown;
+//!
+//! impl Backend for Markdown {
+//!     fn open(&mut self, attrs: &[Attr<'_>], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!         if attrs.contains(&Attr::Style(Styles::Bold)) {
+//!             f.write_str("*")?;
+//!         }
+//!         Ok(())
+//!     }
+//!
+//!     fn close(&mut self, attrs: &[Attr<'_>], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!         self.open(attrs, f)
+//!     }
+//! }
+//!
+//! let value = "important".red().bold();
+//! assert_eq!(value.display_with(Markdown).to_string(), "*important*");
+//! ```
+use crate::display::Plain;
+use crate::link::EncodedUrl;
+use crate::{ColorSupport, ColorSyntax, Colors, Sanitized, Spec, Styles};
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+use std::path;
+
+/// An attribute that a wrapper applies to its value.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Attr<'a> {
+    /// The text color.
+    Fg(Colors),
+    /// The background color.
+    Bg(Colors),
+    /// A style.
+    Style(Styles),
+    /// A hyperlink to the URL.
+    Link(&'a str),
+}
+
+/// Receives the events for rendering a colorized or styled value.
+///
+/// Attributes that are applied together, like the colors and styles of a
+/// [`Spec`], are passed together. Styles come first, then the text color, then the
+/// background color. Wrappers don't check color support or capabilities, so the
+/// backend decides what it can show.
+pub trait Backend {
+    /// Called before a wrapped value is written.
+    fn open(&mut self, attrs: &[Attr<'_>], f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error>;
+
+    /// Called after a wrapped value is written, with the same attributes as the
+    /// matching call to [`open`](Self::open).
+    fn close(&mut self, attrs: &[Attr<'_>], f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error>;
+
+    /// Writes text. The text is written as is by default.
+    fn text(&mut self, text: &str, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(text)
+    }
+}
+
+/// Like [`DisplayWithFallback`](crate::DisplayWithFallback), but sends events to a
+/// [`Backend`] instead of writing escape sequences.
+pub trait DisplayWithBackend {
+    /// Formats the value with the backend.
+    fn fmt_backend(
+        &self,
+        backend: &mut dyn Backend,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error>;
+
+    /// Converts to a type that displays the value with the backend. The backend is
+    /// cloned each time the value is displayed, so every render starts fresh.
+    fn display_with<B: Backend + Clone>(self, backend: B) -> DisplayBackend<Self, B>
+    where
+        Self: Sized,
+    {
+        DisplayBackend {
+            inner: self,
+            backend,
+        }
+    }
+}
+
+/// A type suitable for displaying values with a backend. See [`DisplayWithBackend`].
+pub struct DisplayBackend<D: DisplayWithBackend, B: Backend + Clone> {
+    inner: D,
+    backend: B,
+}
+
+impl<D: DisplayWithBackend, B: Backend + Clone> fmt::Display for DisplayBackend<D, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        self.inner.fmt_backend(&mut self.backend.clone(), f)
+    }
+}
+
+/// Opens the attributes, writes the wrapped value, and closes the attributes. Nothing
+/// is sent to the backend if there are no attributes.
+pub(crate) fn fmt_attrs<F>(
+    backend: &mut dyn Backend,
+    attrs: &[Attr<'_>],
+    f: &mut fmt::Formatter<'_>,
+    wrapped: F,
+) -> Result<(), fmt::Error>
+where
+    F: FnOnce(&mut dyn Backend, &mut fmt::Formatter<'_>) -> Result<(), fmt::Error>,
+{
+    if attrs.is_empty() {
+        return wrapped(backend, f);
+    }
+    backend.open(attrs, f)?;
+    wrapped(backend, f)?;
+    backend.close(attrs, f)
+}
+
+/// Writes escape sequences, like [`DisplayWithFallback`](crate::DisplayWithFallback).
+///
+/// Each group of attributes is written as a single escape sequence, and unsupported
+/// colors are converted to supported colors. Only built-in styles can be shown.
+/// Hyperlinks are written if they are enabled with
+/// [`config::use_hyperlinks`](crate::config::use_hyperlinks).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Ansi {
+    support: ColorSupport,
+    /// The color syntax to use instead of the configured color syntax.
+    syntax: Option<ColorSyntax>,
+    /// For each open group, if an escape sequence and a hyperlink were written.
+    open: Vec<(bool, bool)>,
+}
+
+impl Ansi {
+    /// Creates a backend for the color support.
+    #[inline]
+    pub const fn new(support: ColorSupport) -> Self {
+        Self {
+            support,
+            syntax: None,
+            open: Vec::new(),
+        }
+    }
+
+    /// Writes 8-bit and true colors with the color syntax, instead of the configured
+    /// color syntax.
+    #[inline]
+    pub const fn with_syntax(mut self, syntax: ColorSyntax) -> Self {
+        self.syntax = Some(syntax);
+        self
+    }
+}
+
+impl Backend for Ansi {
+    fn open(&mut self, attrs: &[Attr<'_>], f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let link = attrs.iter().find_map(|attr| match attr {
+            Attr::Link(url) => Some(*url),
+            _ => None,
+        });
+        let link = link.filter(|_| crate::config::get_hyperlinks());
+        if let Some(url) = link {
+            write!(f, "\x1B]8;;{}\x1B\\", EncodedUrl(url))?;
+        }
+        let syntax = self.syntax.unwrap_or_else(crate::config::get_color_syntax);
+        let is_open = Spec::from_attrs(attrs)
+            .resolve(self.support, true)
+            .fmt_raw_with_syntax(syntax, f)?;
+        self.open.push((is_open, link.is_some()));
+        Ok(())
+    }
+
+    fn close(&mut self, _attrs: &[Attr<'_>], f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let (is_open, is_link) = self.open.pop().unwrap_or_default();
+        if is_open {
+            write!(f, "\x1B[0m")?;
+        }
+        if is_link {
+            write!(f, "\x1B]8;;\x1B\\")?;
+        }
+        Ok(())
+    }
+}
+
+impl DisplayWithBackend for str {
+    /// Writes the text with the backend.
+    fn fmt_backend(
+        &self,
+        backend: &mut dyn Backend,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        backend.text(self, f)
+    }
+}
+
+impl<D: DisplayWithBackend + ?Sized> DisplayWithBackend for &D {
+    #[inline]
+    fn fmt_backend(
+        &self,
+        backend: &mut dyn Backend,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        (**self).fmt_backend(backend, f)
+    }
+}
+
+/// Implements [`DisplayWithBackend`] for types that are displayed without any color.
+macro_rules! impl_display_with_backend_plain {
+    ($($t:ty),+ $(,)?) => {
+        $(
+            impl DisplayWithBackend for $t {
+                /// Writes the value as text with the backend.
+                fn fmt_backend(
+                    &self,
+                    backend: &mut dyn Backend,
+                    f: &mut fmt::Formatter<'_>,
+                ) -> Result<(), fmt::Error> {
+                    write!(Text { backend, f }, "{self}")
+                }
+            }
+        )+
+    };
+}
+
+impl_display_with_backend_plain!(
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    f32,
+    f64,
+    char,
+    bool,
+    String,
+    Cow<'_, str>,
+    fmt::Arguments<'_>,
+    path::Display<'_>,
+);
+
+impl<T: fmt::Display> DisplayWithBackend for Plain<T> {
+    /// Writes the value as text with the backend.
+    fn fmt_backend(
+        &self,
+        backend: &mut dyn Backend,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        write!(Text { backend, f }, "{}", self.0)
+    }
+}
+
+impl<T: fmt::Display> DisplayWithBackend for Sanitized<T> {
+    /// Writes the sanitized value as text with the backend.
+    fn fmt_backend(
+        &self,
+        backend: &mut dyn Backend,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        self.fmt_sanitized(Text { backend, f })
+    }
+}
+
+/// Sends everything that is written to a backend as text.
+struct Text<'a, 'b> {
+    backend: &'a mut dyn Backend,
+    f: &'a mut fmt::Formatter<'b>,
+}
+
+impl Write for Text<'_, '_> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
+        self.backend.text(s, self.f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Colorize, DisplayWithFallback, Stylize};
+    use rstest::rstest;
+
+    /// Writes attributes as bracketed markers, like `[fg=red]text[/]`.
+    #[derive(Clone)]
+    struct Markers;
+
+    impl Backend for Markers {
+        fn open(
+            &mut self,
+            attrs: &[Attr<'_>],
+            f: &mut fmt::Formatter<'_>,
+        ) -> Result<(), fmt::Error> {
+            let attrs: Vec<_> = attrs
+                .iter()
+                .map(|attr| match attr {
+                    Attr::Fg(color) => format!("fg={color:?}"),
+                    Attr::Bg(color) => format!("bg={color:?}"),
+                    Attr::Style(style) => format!("{style:?}"),
+                    Attr::Link(url) => format!("link={url}"),
+                })
+                .collect();
+            write!(f, "[{}]", attrs.join(" "))
+        }
+
+        fn close(
+            &mut self,
+            _attrs: &[Attr<'_>],
+            f: &mut fmt::Formatter<'_>,
+        ) -> Result<(), fmt::Error> {
+            f.write_str("[/]")
+        }
+
+        fn text(&mut self, text: &str, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+            f.write_str(&text.to_uppercase())
+        }
+    }
+
+    #[test]
+    fn test_markers() {
+        let value = "x".red().bold().link("url").styled().on_blue().italic();
+        assert_eq!(
+            value.display_with(Markers).to_string(),
+            "[Italic bg=Simple(Blue)][link=url][Bold][fg=Simple(Red)]X[/][/][/][/]"
+        );
+        assert_eq!(42.display_with(Markers).to_string(), "42");
+    }
+
+    #[rstest]
+    #[case(ColorSupport::True)]
+    #[case(ColorSupport::EightBit)]
+    #[case(ColorSupport::Simple)]
+    #[case(ColorSupport::None)]
+    fn test_ansi_matches_fallback(#[case] support: ColorSupport) {
+        let value = "x"
+            .rgb(255, 0, 0)
+            .on_eight_bit(208)
+            .bold()
+            .styled()
+            .italic()
+            .blue();
+        assert_eq!(
+            (&value).display_with(Ansi::new(support)).to_string(),
+            value.display_fallback(support).to_string()
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Filters text with ANSI escape sequences from stdin to stdout.
+//!
+//! This is built with the `cli` feature.
+use chromaterm::ColorSupport;
+use chromaterm::ansi::{DowngradeWriter, StripWriter};
+use chromaterm::html::{Html, HtmlWriter};
+use chromaterm::svg::Svg;
+use std::io::{self, BufWriter, Read, Write};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: chromaterm <COMMAND>
+
+Reads text with ANSI escape sequences from stdin, and writes the result to stdout.
+
+Commands:
+  strip                   Remove all escape sequences
+  downgrade --to <COLORS> Convert colors to 256 or 16 colors
+  to-html [--classes]     Convert to HTML, using CSS classes if --classes is given
+  to-svg                  Render as an SVG image
+  detect                  Print the detected color support, and why it was detected
+
+Options:
+  -h, --help              Print this help";
+
+/// A parsed command.
+#[derive(Debug, PartialEq)]
+enum Command {
+    Strip,
+    Downgrade(ColorSupport),
+    ToHtml { classes: bool },
+    ToSvg,
+    Detect,
+    Help,
+}
+
+impl Command {
+    /// Parses the command from the arguments, not including the program name.
+    fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut args = args.into_iter();
+        let Some(command) = args.next() else {
+            return Err("missing command".to_owned());
+        };
+        let rest: Vec<String> = args.collect();
+        if rest.iter().any(|arg| arg == "-h" || arg == "--help") {
+            return Ok(Self::Help);
+        }
+        let command = match (command.as_str(), rest.as_slice()) {
+            ("-h" | "--help", _) => Self::Help,
+            ("strip", []) => Self::Strip,
+            ("downgrade", [flag, colors]) if flag == "--to" => Self::Downgrade(colors_arg(colors)?),
+            ("downgrade", [flag]) => match flag.strip_prefix("--to=") {
+                Some(colors) => Self::Downgrade(colors_arg(colors)?),
+                None => return Err("downgrade requires --to <COLORS>".to_owned()),
+            },
+            ("downgrade", _) => return Err("downgrade requires --to <COLORS>".to_owned()),
+            ("to-html", []) => Self::ToHtml { classes: false },
+            ("to-html", [flag]) if flag == "--classes" => Self::ToHtml { classes: true },
+            ("to-svg", []) => Self::ToSvg,
+            ("detect", []) => Self::Detect,
+            ("strip" | "to-html" | "to-svg" | "detect", [arg, ..]) => {
+                return Err(format!("unexpected argument {arg:?} for {command}"));
+            }
+            _ => return Err(format!("unknown command {command:?}")),
+        };
+        Ok(command)
+    }
+
+    /// Runs the command.
+    fn run(self) -> io::Result<()> {
+        let mut stdin = io::stdin().lock();
+        let stdout = BufWriter::new(io::stdout().lock());
+        match self {
+            Self::Strip => {
+                let mut writer = StripWriter::new(stdout);
+                io::copy(&mut stdin, &mut writer)?;
+                writer.into_inner().flush()
+            }
+            Self::Downgrade(supported) => {
+                let mut writer = DowngradeWriter::new(stdout, supported);
+                io::copy(&mut stdin, &mut writer)?;
+                writer.into_inner().flush()
+            }
+            Self::ToHtml { classes } => {
+                let html = Html::new(ColorSupport::True).with_classes(classes);
+                let mut writer = HtmlWriter::new(stdout, html);
+                io::copy(&mut stdin, &mut writer)?;
+                writer.finish()?;
+                Ok(())
+            }
+            Self::ToSvg => {
+                let mut text = String::new();
+                stdin.read_to_string(&mut text)?;
+                let mut stdout = stdout;
+                // NOTE Output usually ends with a newline, which shouldn't add an empty
+                //      line to the image.
+                let text = text.strip_suffix('\n').unwrap_or(&text);
+                writeln!(stdout, "{}", Svg::new().render(text))?;
+                stdout.flush()
+            }
+            Self::Detect => {
+                let (supported, reason) = ColorSupport::from_env_explained();
+                let mut stdout = stdout;
+                writeln!(stdout, "{supported:?}")?;
+                writeln!(stdout, "{reason}")?;
+                stdout.flush()
+            }
+            Self::Help => {
+                let mut stdout = stdout;
+                writeln!(stdout, "{USAGE}")?;
+                stdout.flush()
+            }
+        }
+    }
+}
+
+/// Parses the argument of `downgrade --to`.
+fn colors_arg(colors: &str) -> Result<ColorSupport, String> {
+    match colors {
+        "256" => Ok(ColorSupport::EightBit),
+        "16" => Ok(ColorSupport::Simple),
+        _ => Err(format!("expected 256 or 16 colors, got {colors:?}")),
+    }
+}
+
+fn main() -> ExitCode {
+    let command = match Command::parse(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match command.run() {
+        Ok(()) => ExitCode::SUCCESS,
+        // NOTE The output may be piped to a command like `head` that stops reading.
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(&["strip"], Ok(Command::Strip))]
+    #[case(&["downgrade", "--to", "256"], Ok(Command::Downgrade(ColorSupport::EightBit)))]
+    #[case(&["downgrade", "--to=16"], Ok(Command::Downgrade(ColorSupport::Simple)))]
+    #[case(
+        &["downgrade", "--to", "8"],
+        Err("expected 256 or 16 colors, got \"8\"".to_owned())
+    )]
+    #[case(&["downgrade"], Err("downgrade requires --to <COLORS>".to_owned()))]
+    #[case(&["to-html", "--classes"], Ok(Command::ToHtml { classes: true }))]
+    #[case(&[], Err("missing command".to_owned()))]
+    #[case(&["paint"], Err("unknown command \"paint\"".to_owned()))]
+    #[case(&["-h"], Ok(Command::Help))]
+    #[case(&["downgrade", "--help"], Ok(Command::Help))]
+    fn test_parse(#[case] args: &[&str], #[case] expected: Result<Command, String>) {
+        let args = args.iter().map(|arg| (*arg).to_owned());
+        assert_eq!(Command::parse(args), expected);
+    }
+}
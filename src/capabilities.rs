@@ -0,0 +1,206 @@
+use std::env;
+use std::ops::{BitAnd, BitOr, BitOrAssign, Not};
+
+/// A set of terminal capabilities that vary independently of [`ColorSupport`].
+///
+/// Color support is a spectrum, but support for things like italics or hyperlinks
+/// doesn't follow it. A terminal might support true colors, but not curly underlines,
+/// while another only supports 16 colors, but does support hyperlinks.
+///
+/// Styles declare the capability they require with
+/// [`Style::capability`](crate::Style::capability). Styles that require a capability
+/// that isn't in the configured set are skipped, or substituted when converting to
+/// supported styles.
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::Capabilities;
+///
+/// let capabilities = Capabilities::ITALIC | Capabilities::HYPERLINKS;
+/// assert!(capabilities.contains(Capabilities::ITALIC));
+/// assert!(!capabilities.contains(Capabilities::ITALIC | Capabilities::BLINK));
+/// ```
+///
+/// [`ColorSupport`]: crate::ColorSupport
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub struct Capabilities(u8);
+
+impl Capabilities {
+    /// No capabilities.
+    pub const NONE: Self = Self(0);
+    /// Italic text.
+    pub const ITALIC: Self = Self(1 << 0);
+    /// Struck-through text.
+    pub const STRIKETHROUGH: Self = Self(1 << 1);
+    /// Curly (or other styled) underlines.
+    pub const CURLY_UNDERLINE: Self = Self(1 << 2);
+    /// OSC 8 hyperlinks.
+    pub const HYPERLINKS: Self = Self(1 << 3);
+    /// Blinking text.
+    pub const BLINK: Self = Self(1 << 4);
+    /// All capabilities.
+    pub const ALL: Self = Self(0b11111);
+
+    /// The capabilities that are assumed if nothing else is known. This only includes
+    /// capabilities that were widely supported by terminals with *any* color support.
+    pub const BASIC: Self = Self(Self::ITALIC.0 | Self::STRIKETHROUGH.0 | Self::BLINK.0);
+
+    /// Gets the raw bits.
+    #[inline]
+    pub const fn bits(&self) -> u8 {
+        self.0
+    }
+
+    /// Creates from raw bits, ignoring unknown bits.
+    #[inline]
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits & Self::ALL.0)
+    }
+
+    /// Checks if *all* capabilities in `other` are in this set.
+    #[inline]
+    pub const fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Checks if the set has no capabilities.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Adds the capabilities in `other` to this set.
+    #[inline]
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    /// Removes the capabilities in `other` from this set.
+    #[inline]
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+
+    /// Adds or removes the capabilities in `other`.
+    #[inline]
+    pub fn set(&mut self, other: Self, enabled: bool) {
+        if enabled {
+            self.insert(other);
+        } else {
+            self.remove(other);
+        }
+    }
+
+    /// Detects capabilities from environment variables.
+    pub fn from_env() -> Self {
+        Self::from_vars(|key| env::var(key).ok())
+    }
+
+    /// Detects capabilities from environment variables, using `var` to look them up.
+    fn from_vars<V: Fn(&str) -> Option<String>>(var: V) -> Self {
+        let term = var("TERM").unwrap_or_default();
+        let mut capabilities = match term.as_str() {
+            "dumb" => Self::NONE,
+            // NOTE The Linux console and real VT terminals show italics and strikes as
+            //      colors, if at all.
+            "linux" | "vt100" | "vt102" | "vt220" => Self::BLINK,
+            _ => Self::BASIC,
+        };
+
+        // NOTE VTE 0.51.2 added curly underlines.
+        if is_modern_terminal(&var, 5102) {
+            capabilities.insert(Self::CURLY_UNDERLINE);
+        }
+        capabilities.set(Self::HYPERLINKS, crate::link::hyperlinks_from_vars(&var));
+        capabilities
+    }
+}
+
+/// Checks if the environment variables show a terminal that is known to support
+/// newer extensions, like curly underlines and colon-separated colors.
+///
+/// VTE-based terminals gained these extensions over several releases, so the caller
+/// gives the first VTE version that supports what it needs, in the form of
+/// `VTE_VERSION`, like `5102` for 0.51.2.
+pub(crate) fn is_modern_terminal<V: Fn(&str) -> Option<String>>(
+    var: V,
+    min_vte_version: u32,
+) -> bool {
+    let term = var("TERM").unwrap_or_default();
+    let term_program = var("TERM_PROGRAM").unwrap_or_default();
+    let vte_version = var("VTE_VERSION").and_then(|version| version.parse::<u32>().ok());
+    ["kitty", "wezterm", "foot", "ghostty", "contour"]
+        .into_iter()
+        .any(|hint| term.contains(hint))
+        || ["WezTerm", "ghostty"].contains(&term_program.as_str())
+        || vte_version.is_some_and(|version| version >= min_vte_version)
+}
+
+impl Default for Capabilities {
+    #[inline]
+    fn default() -> Self {
+        Self::BASIC
+    }
+}
+
+impl BitOr for Capabilities {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Capabilities {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs);
+    }
+}
+
+impl BitAnd for Capabilities {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl Not for Capabilities {
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        Self(!self.0 & Self::ALL.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(&[("TERM", "dumb")], Capabilities::NONE)]
+    #[case(&[("TERM", "vt100")], Capabilities::BLINK)]
+    #[case(&[("TERM", "xterm-256color")], Capabilities::BASIC)]
+    #[case(
+        &[("TERM", "xterm-kitty")],
+        Capabilities::BASIC | Capabilities::CURLY_UNDERLINE | Capabilities::HYPERLINKS
+    )]
+    #[case(
+        &[("TERM", "xterm-256color"), ("VTE_VERSION", "7600")],
+        Capabilities::BASIC | Capabilities::CURLY_UNDERLINE | Capabilities::HYPERLINKS
+    )]
+    #[case(
+        &[("TERM", "xterm-256color"), ("WT_SESSION", "1")],
+        Capabilities::BASIC | Capabilities::HYPERLINKS
+    )]
+    fn test_from_vars(#[case] vars: &[(&str, &str)], #[case] expected: Capabilities) {
+        let var = crate::color_support::lookup_vars(vars);
+        assert_eq!(Capabilities::from_vars(var), expected);
+    }
+}
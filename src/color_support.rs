@@ -22,6 +22,15 @@ impl ColorSupport {
             .unwrap_or(Self::None)
     }
 
+    /// Calculates the color support like
+    /// [`config::use_default_color_support`](crate::config::use_default_color_support),
+    /// from environment variables and `NO_COLOR`, and describes why it was chosen.
+    ///
+    /// This is useful for debugging color detection.
+    pub fn from_env_explained() -> (Self, String) {
+        Self::explain_vars(|key| env::var(key).ok())
+    }
+
     /// Checks if the color level is supported.
     ///
     /// Not that this is *not* the same as a simple equality check.
@@ -48,14 +57,39 @@ impl ColorSupport {
 
     /// Calculate from the `TERM` or `COLORTERM` environment variable.
     fn from_term_value<S: AsRef<str>>(value: S) -> Option<Self> {
-        let value = value.as_ref();
+        Self::term_hint(value.as_ref()).map(|(_, variant)| variant)
+    }
+
+    /// Finds the hint in the value of the `TERM` or `COLORTERM` environment variable
+    /// that decides the color support.
+    fn term_hint(value: &str) -> Option<(&'static str, Self)> {
         [
             ("256", Self::EightBit),
             ("24bit", Self::True),
             ("truecolor", Self::True),
         ]
         .into_iter()
-        .find_map(|(hint, variant)| value.contains(hint).then_some(variant))
+        .find(|(hint, _)| value.contains(hint))
+    }
+
+    /// Calculates the color support and describes why, using `var` to look up
+    /// environment variables.
+    fn explain_vars<V: Fn(&str) -> Option<String>>(var: V) -> (Self, String) {
+        if let Some(value) = var("NO_COLOR").filter(|value| env_value_to_bool(value)) {
+            return (Self::None, format!("NO_COLOR is {value:?}"));
+        }
+        let detected = ["COLORTERM", "TERM"].into_iter().find_map(|key| {
+            let value = var(key)?;
+            let (hint, variant) = Self::term_hint(&value)?;
+            Some((
+                variant,
+                format!("{key} is {value:?}, which contains {hint:?}"),
+            ))
+        });
+        detected.unwrap_or_else(|| {
+            let reason = "neither COLORTERM nor TERM contains \"256\", \"24bit\", or \"truecolor\"";
+            (Self::None, reason.to_owned())
+        })
     }
 }
 
@@ -88,6 +122,17 @@ fn env_value_to_bool<S: AsRef<str>>(value: S) -> bool {
     matches!(value.as_ref(), "1" | "true")
 }
 
+/// Looks up environment variables in a list, to test detection from environment
+/// variables without changing the real environment.
+#[cfg(test)]
+pub(crate) fn lookup_vars<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+    |key| {
+        vars.iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| (*v).to_owned())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -101,6 +146,23 @@ mod tests {
         assert_eq!(ColorSupport::from_term_value(value), expected);
     }
 
+    #[rstest]
+    #[case(&[("COLORTERM", "truecolor"), ("TERM", "xterm-256color")], ColorSupport::True, "COLORTERM is \"truecolor\", which contains \"truecolor\"")]
+    #[case(&[("COLORTERM", "yes"), ("TERM", "xterm-256color")], ColorSupport::EightBit, "TERM is \"xterm-256color\", which contains \"256\"")]
+    #[case(&[("NO_COLOR", "1"), ("COLORTERM", "24bit")], ColorSupport::None, "NO_COLOR is \"1\"")]
+    #[case(&[("TERM", "xterm")], ColorSupport::None, "neither COLORTERM nor TERM contains \"256\", \"24bit\", or \"truecolor\"")]
+    fn test_explain_vars(
+        #[case] vars: &[(&str, &str)],
+        #[case] expected: ColorSupport,
+        #[case] expected_reason: &str,
+    ) {
+        let var = lookup_vars(vars);
+        assert_eq!(
+            ColorSupport::explain_vars(var),
+            (expected, expected_reason.to_owned())
+        );
+    }
+
     #[rstest]
     #[case(ColorSupport::Simple, ColorSupport::Simple, true)]
     #[case(ColorSupport::EightBit, ColorSupport::Simple, true)]
@@ -0,0 +1,144 @@
+use crate::Layer;
+use std::env;
+use std::fmt;
+
+/// How 8-bit and true colors are written in escape sequences.
+///
+/// Most terminals understand the semicolon form, like `38;2;255;0;0`, which is the
+/// default. ITU T.416 specifies the colon form, like `38:2::255:0:0`, which keeps the
+/// color's arguments in a single parameter. Parsers that don't understand extended
+/// colors can then skip the whole color, instead of reading its arguments as other
+/// styles. Simple colors are always written the same way.
+///
+/// Set this globally with
+/// [`config::use_color_syntax`](crate::config::use_color_syntax), or for a single
+/// render with [`Ansi::with_syntax`](crate::backend::Ansi::with_syntax).
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::prelude::*;
+/// use chromaterm::backend::Ansi;
+/// use chromaterm::{ColorSupport, ColorSyntax};
+///
+/// let ansi = Ansi::new(ColorSupport::True).with_syntax(ColorSyntax::Colon);
+/// let value = "x".rgb(255, 0, 0).display_with(ansi);
+/// assert_eq!(value.to_string(), "\x1B[38:2::255:0:0mx\x1B[0m");
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub enum ColorSyntax {
+    /// `38;5;n` and `38;2;r;g;b`.
+    #[default]
+    Semicolon,
+    /// `38:5:n` and `38:2::r:g:b`, with an empty color space ID.
+    Colon,
+    /// `38:5:n` and `38:2:0:r:g:b`, with the default color space ID.
+    ColonWithColorspace,
+}
+
+impl ColorSyntax {
+    /// Detects the color syntax from environment variables. The colon form is only
+    /// chosen for terminals that are known to understand it.
+    pub fn from_env() -> Self {
+        Self::from_vars(|key| env::var(key).ok())
+    }
+
+    /// Detects the color syntax from environment variables, using `var` to look them
+    /// up.
+    fn from_vars<V: Fn(&str) -> Option<String>>(var: V) -> Self {
+        // NOTE VTE only reads colon-separated colors since 0.52, which is later than
+        //      it added curly underlines.
+        let understands_colons = crate::capabilities::is_modern_terminal(var, 5200);
+        if understands_colons {
+            Self::Colon
+        } else {
+            Self::Semicolon
+        }
+    }
+
+    /// The separator between the arguments of a color.
+    #[inline]
+    pub(crate) const fn separator(self) -> char {
+        match self {
+            Self::Semicolon => ';',
+            Self::Colon | Self::ColonWithColorspace => ':',
+        }
+    }
+
+    /// The parameter that starts an extended color.
+    #[inline]
+    const fn introducer(layer: Layer) -> u8 {
+        match layer {
+            Layer::Fg => 38,
+            Layer::Bg => 48,
+        }
+    }
+
+    /// The arguments between `2` and the RGB values of a true color, including the
+    /// separators.
+    #[inline]
+    pub(crate) const fn true_color_infix(self) -> &'static str {
+        match self {
+            Self::Semicolon => ";",
+            Self::Colon => "::",
+            Self::ColonWithColorspace => ":0:",
+        }
+    }
+
+    /// Writes the parameter for an 8-bit color.
+    pub(crate) fn fmt_eight_bit(
+        self,
+        layer: Layer,
+        lookup: u8,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        let separator = self.separator();
+        let introducer = Self::introducer(layer);
+        write!(f, "{introducer}{separator}5{separator}{lookup}")
+    }
+
+    /// Writes the parameter for a true color.
+    pub(crate) fn fmt_true(
+        self,
+        layer: Layer,
+        (r, g, b): (u8, u8, u8),
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        let separator = self.separator();
+        let introducer = Self::introducer(layer);
+        let infix = self.true_color_infix();
+        write!(
+            f,
+            "{introducer}{separator}2{infix}{r}{separator}{g}{separator}{b}"
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::FmtFn;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(ColorSyntax::Semicolon, "38;5;208", "48;2;1;2;3")]
+    #[case(ColorSyntax::Colon, "38:5:208", "48:2::1:2:3")]
+    #[case(ColorSyntax::ColonWithColorspace, "38:5:208", "48:2:0:1:2:3")]
+    fn test_fmt(#[case] syntax: ColorSyntax, #[case] eight_bit: &str, #[case] true_color: &str) {
+        let fmt_eight_bit = FmtFn(|f| syntax.fmt_eight_bit(Layer::Fg, 208, f));
+        assert_eq!(fmt_eight_bit.to_string(), eight_bit);
+        let fmt_true = FmtFn(|f| syntax.fmt_true(Layer::Bg, (1, 2, 3), f));
+        assert_eq!(fmt_true.to_string(), true_color);
+    }
+
+    #[rstest]
+    #[case(&[("TERM", "xterm-256color")], ColorSyntax::Semicolon)]
+    #[case(&[("TERM", "xterm-kitty")], ColorSyntax::Colon)]
+    #[case(&[("TERM", "xterm-256color"), ("TERM_PROGRAM", "WezTerm")], ColorSyntax::Colon)]
+    #[case(&[("TERM", "xterm-256color"), ("VTE_VERSION", "5202")], ColorSyntax::Colon)]
+    #[case(&[("TERM", "xterm-256color"), ("VTE_VERSION", "5000")], ColorSyntax::Semicolon)]
+    fn test_from_vars(#[case] vars: &[(&str, &str)], #[case] expected: ColorSyntax) {
+        let var = crate::color_support::lookup_vars(vars);
+        assert_eq!(ColorSyntax::from_vars(var), expected);
+    }
+}
@@ -1,5 +1,10 @@
-use crate::{Color, ColorLevel, ColorSupport, DisplayWithExact, DisplayWithFallback, conversion};
-use std::fmt;
+use crate::backend::{self, Attr, Backend, DisplayWithBackend};
+use crate::write_to::{self, WriteTo};
+use crate::{
+    Color, ColorLevel, ColorSupport, Colors, DisplayWithExact, DisplayWithFallback, Layer, Style,
+    conversion,
+};
+use std::{fmt, io};
 
 /// Wraps a value that can be colorized.
 pub struct Colorizer<C: Color, D> {
@@ -23,6 +28,11 @@ impl<C: Color, D> Colorizer<C, D> {
             value,
         }
     }
+
+    /// Reapplies the colors and styles on every line. See [`PerLine`](crate::PerLine).
+    pub fn per_line(self) -> crate::PerLine<Self> {
+        crate::PerLine::new(self)
+    }
 }
 
 impl<C: Color, D: DisplayWithExact> DisplayWithExact for Colorizer<C, D> {
@@ -44,6 +54,12 @@ impl<C: Color, D: DisplayWithExact> DisplayWithExact for Colorizer<C, D> {
         }
         Ok(())
     }
+
+    /// Aligns like the contained value.
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        self.value.default_alignment()
+    }
 }
 
 impl<C: Color + conversion::ToSimple + conversion::ToEightBit, D: DisplayWithFallback>
@@ -57,7 +73,21 @@ impl<C: Color + conversion::ToSimple + conversion::ToEightBit, D: DisplayWithFal
     ) -> Result<(), fmt::Error> {
         let level = self.color.level();
         match (supported, level) {
-            (ColorSupport::None, _) => self.value.fmt_fallback(supported, f),
+            (ColorSupport::None, _) => {
+                let monochrome = crate::monochrome::monochrome_for(supported);
+                let style = match &self.color {
+                    ColorZone::Fg(c) => monochrome.to_style(&Colors::from_color(c), Layer::Fg),
+                    ColorZone::Bg(c) => monochrome.to_style(&Colors::from_color(c), Layer::Bg),
+                };
+                let Some(style) = style else {
+                    return self.value.fmt_fallback(supported, f);
+                };
+                write!(f, "\x1B[")?;
+                style.fmt_style(f)?;
+                write!(f, "m")?;
+                self.value.fmt_fallback(supported, f)?;
+                write!(f, "\x1B[0m")
+            }
             // NOTE Since True is the highest level, we can avoid conversion for all branches.
             (ColorSupport::True, _)
             | (ColorSupport::EightBit, ColorLevel::EightBit)
@@ -83,6 +113,62 @@ impl<C: Color + conversion::ToSimple + conversion::ToEightBit, D: DisplayWithFal
             }
         }
     }
+
+    /// Aligns like the contained value.
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        self.value.default_alignment()
+    }
+}
+
+impl<C: Color + conversion::ToSimple + conversion::ToEightBit, D: DisplayWithBackend>
+    DisplayWithBackend for Colorizer<C, D>
+{
+    /// Sends the color to the backend.
+    fn fmt_backend(
+        &self,
+        backend: &mut dyn Backend,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        let attr = match &self.color {
+            ColorZone::Fg(c) => Attr::Fg(Colors::from_color(c)),
+            ColorZone::Bg(c) => Attr::Bg(Colors::from_color(c)),
+        };
+        backend::fmt_attrs(backend, &[attr], f, |backend, f| {
+            self.value.fmt_backend(backend, f)
+        })
+    }
+}
+
+impl<C: Color + conversion::ToSimple + conversion::ToEightBit, D: WriteTo> WriteTo
+    for Colorizer<C, D>
+{
+    /// Writes the contained value like [`DisplayWithFallback`], but without formatting.
+    fn write_to<W: io::Write + ?Sized>(
+        &self,
+        w: &mut W,
+        supported: ColorSupport,
+    ) -> io::Result<()> {
+        let (color, layer) = match &self.color {
+            ColorZone::Fg(c) => (Colors::from_color(c), Layer::Fg),
+            ColorZone::Bg(c) => (Colors::from_color(c), Layer::Bg),
+        };
+        match color.fallback(supported) {
+            Some(supported_color) => {
+                let syntax = crate::config::get_color_syntax();
+                write_to::write_color(w, supported_color, layer == Layer::Bg, syntax)?;
+            }
+            None => {
+                let monochrome = crate::monochrome::monochrome_for(supported);
+                let Some(style) = monochrome.to_style(&color, layer) else {
+                    return self.value.write_to(w, supported);
+                };
+                w.write_all(write_to::style_sequence(style))?;
+            }
+        }
+        self.value.write_to(w, supported)?;
+        w.write_all(write_to::RESET)
+    }
 }
 
 impl<
@@ -91,14 +177,7 @@ impl<
 > fmt::Display for Colorizer<C, D>
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        let support = crate::config::get_color_support();
-        let should_fallback = crate::config::get_convert_to_supported();
-
-        if should_fallback {
-            self.fmt_fallback(support, f)
-        } else {
-            self.fmt_exact(support, f)
-        }
+        crate::display::fmt_configured(self, f)
     }
 }
 
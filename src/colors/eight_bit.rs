@@ -1,7 +1,7 @@
 use super::Simple;
 use super::simple::*;
 use crate::conversion::{ToEightBit, ToSimple};
-use crate::{Color, ColorLevel};
+use crate::{Color, ColorLevel, Layer};
 use std::fmt;
 
 /// An eight-bit color that uses a lookup table.
@@ -14,6 +14,12 @@ use std::fmt;
 pub struct EightBit(u8);
 
 impl EightBit {
+    /// Gets the index in the lookup table.
+    #[inline]
+    pub const fn lookup(&self) -> u8 {
+        self.0
+    }
+
     /// Gets the RGB lookup color for the 6x6x6 cube.
     const fn cube_rgb_lookup(&self) -> (u8, u8, u8) {
         // TODO Simplify. Converting to the 6x6x6 and *then* converting to an eight-bit
@@ -56,11 +62,11 @@ impl From<u8> for EightBit {
 
 impl Color for EightBit {
     fn fmt_fg(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        write!(f, "38;5;{}", self.0)
+        crate::config::get_color_syntax().fmt_eight_bit(Layer::Fg, self.0, f)
     }
 
     fn fmt_bg(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        write!(f, "48;5;{}", self.0)
+        crate::config::get_color_syntax().fmt_eight_bit(Layer::Bg, self.0, f)
     }
 
     fn level(&self) -> ColorLevel {
@@ -1,16 +1,19 @@
 //! Collection of color types.
 use crate::conversion::{ToEightBit, ToSimple};
-use crate::{Color, ColorLevel};
+use crate::{Color, ColorLevel, ColorSupport, ColorSyntax, Layer};
 pub use eight_bit::EightBit;
+pub use palette::Palette;
 pub use simple::Simple;
 use std::fmt;
 pub use true_color::True;
 
 mod eight_bit;
+mod palette;
 pub mod simple;
 mod true_color;
 
 /// Unifies all color types.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum Colors {
     Simple(Simple),
     EightBit(EightBit),
@@ -25,6 +28,52 @@ impl Colors {
     pub fn new_eight_bit(lookup: u8) -> Self {
         Self::EightBit(EightBit::from(lookup))
     }
+
+    /// Converts from any color, keeping the color's level.
+    pub fn from_color<C: Color + ToSimple + ToEightBit>(color: &C) -> Self {
+        match color.level() {
+            ColorLevel::Simple => Self::Simple(color.to_simple()),
+            ColorLevel::EightBit => Self::EightBit(color.to_eight_bit()),
+            ColorLevel::True => {
+                let (r, g, b) = color.rgb_u8();
+                Self::new_true_color(r, g, b)
+            }
+        }
+    }
+
+    /// Gets the color, only if it is supported.
+    pub(crate) fn exact(self, supported: ColorSupport) -> Option<Self> {
+        supported.is_supported(self.level().into()).then_some(self)
+    }
+
+    /// Writes the parameter for the color on the layer, using the color syntax instead
+    /// of the configured one.
+    pub(crate) fn fmt_with_syntax(
+        &self,
+        layer: Layer,
+        syntax: ColorSyntax,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        match (self, layer) {
+            (Self::Simple(c), Layer::Fg) => c.fmt_fg(f),
+            (Self::Simple(c), Layer::Bg) => c.fmt_bg(f),
+            (Self::EightBit(c), _) => syntax.fmt_eight_bit(layer, c.lookup(), f),
+            (Self::True(c), _) => syntax.fmt_true(layer, c.rgb_u8(), f),
+        }
+    }
+
+    /// Converts to a color that is supported. Returns `None` if there is no color
+    /// support at all.
+    pub(crate) fn fallback(self, supported: ColorSupport) -> Option<Self> {
+        let color = match (supported, self.level()) {
+            (ColorSupport::None, _) => return None,
+            (ColorSupport::Simple, ColorLevel::EightBit)
+            | (ColorSupport::Simple, ColorLevel::True) => Self::Simple(self.to_simple()),
+            (ColorSupport::EightBit, ColorLevel::True) => Self::EightBit(self.to_eight_bit()),
+            _ => self,
+        };
+        Some(color)
+    }
 }
 
 macro_rules! impl_new_simple_variant {
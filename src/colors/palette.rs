@@ -0,0 +1,73 @@
+use super::{Colors, EightBit, Simple, True};
+use crate::Color;
+use crate::conversion::ToSimple;
+
+/// The RGB values that the 16 simple colors are shown as.
+///
+/// Terminals let users pick what the simple colors look like, so there is no single
+/// correct RGB value for "red." Renderers that have to pick one, like HTML, use a
+/// palette. 8-bit colors 0 to 15 are the simple colors, so they use the palette too.
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::colors::{Palette, Simple, True};
+/// use chromaterm::Colors;
+///
+/// let palette = Palette::default().with(Simple::Red, True::from_rgb(0xCC, 0, 0));
+/// assert_eq!(palette.rgb(&Colors::new_red()), (0xCC, 0, 0));
+/// assert_eq!(palette.rgb(&Colors::new_eight_bit(1)), (0xCC, 0, 0));
+/// ```
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Palette {
+    colors: [True; 16],
+}
+
+impl Palette {
+    /// Creates a palette from the RGB values of the simple colors, in the order of
+    /// their escape codes: black, red, green, yellow, blue, magenta, cyan, white, and
+    /// then the bright variants.
+    #[inline]
+    pub const fn new(colors: [True; 16]) -> Self {
+        Self { colors }
+    }
+
+    /// Gets the RGB value of a simple color.
+    #[inline]
+    pub fn get(&self, color: Simple) -> True {
+        self.colors[color as usize]
+    }
+
+    /// Sets the RGB value of a simple color.
+    #[inline]
+    pub fn set(&mut self, color: Simple, value: True) {
+        self.colors[color as usize] = value;
+    }
+
+    /// Sets the RGB value of a simple color, and returns the palette.
+    pub fn with(mut self, color: Simple, value: True) -> Self {
+        self.set(color, value);
+        self
+    }
+
+    /// Gets the RGB value of any color, using the palette for simple colors.
+    pub fn rgb(&self, color: &Colors) -> (u8, u8, u8) {
+        let simple = match color {
+            Colors::Simple(simple) => *simple,
+            Colors::EightBit(eight_bit) if eight_bit.lookup() < 16 => eight_bit.to_simple(),
+            Colors::EightBit(_) | Colors::True(_) => return color.rgb_u8(),
+        };
+        self.get(simple).rgb_u8()
+    }
+}
+
+impl Default for Palette {
+    /// The palette that the simple colors use for their own RGB values.
+    fn default() -> Self {
+        let colors = std::array::from_fn(|index| {
+            let (r, g, b) = EightBit::from(index as u8).to_simple().rgb_u8();
+            True::from_rgb(r, g, b)
+        });
+        Self { colors }
+    }
+}
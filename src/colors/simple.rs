@@ -82,6 +82,12 @@ macro_rules! impl_simple_color {
                 Self::$c
             }
         }
+
+        impl From<$c> for super::Colors {
+            fn from(_: $c) -> Self {
+                Self::Simple(Simple::$c)
+            }
+        }
     };
 }
 
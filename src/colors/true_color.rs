@@ -1,10 +1,11 @@
 use super::{EightBit, Simple};
 use crate::conversion::{ToEightBit, ToSimple};
-use crate::{Color, ColorLevel};
+use crate::{Color, ColorLevel, Layer};
 use std::error::Error;
 use std::fmt;
 
 /// A true color value.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub struct True {
     r: u8,
     g: u8,
@@ -66,11 +67,11 @@ impl True {
 
 impl Color for True {
     fn fmt_fg(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        write!(f, "38;2;{};{};{}", self.r, self.g, self.b)
+        crate::config::get_color_syntax().fmt_true(Layer::Fg, self.rgb_u8(), f)
     }
 
     fn fmt_bg(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        write!(f, "48;2;{};{};{}", self.r, self.g, self.b)
+        crate::config::get_color_syntax().fmt_true(Layer::Bg, self.rgb_u8(), f)
     }
 
     fn level(&self) -> ColorLevel {
@@ -1,13 +1,17 @@
 //! Get and store global configuration.
-use crate::ColorSupport;
-use std::sync::atomic::{AtomicU8, Ordering};
+// NOTE Binary literals are grouped by config field, not by nibble.
+#![allow(clippy::unusual_byte_groupings)]
+use crate::theme::Theme;
+use crate::{Capabilities, ColorSupport, ColorSyntax, Monochrome};
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{PoisonError, RwLock};
 
 const ORDERING: Ordering = Ordering::Relaxed;
 
 impl ColorSupport {
-    const BIT_SHIFT: u8 = 6;
+    const BIT_SHIFT: u16 = 6;
 
-    const fn as_config(&self) -> u8 {
+    const fn as_config(&self) -> u16 {
         let value = match self {
             Self::None => 0b00,
             Self::Simple => 0b01,
@@ -17,8 +21,8 @@ impl ColorSupport {
         value << Self::BIT_SHIFT
     }
 
-    fn from_config(config: u8) -> Self {
-        const MASK: u8 = 0b11_000000;
+    fn from_config(config: u16) -> Self {
+        const MASK: u16 = 0b00000000_11_000000;
         match (config & MASK) >> Self::BIT_SHIFT {
             0b00 => Self::None,
             0b01 => Self::Simple,
@@ -28,8 +32,8 @@ impl ColorSupport {
         }
     }
 
-    const fn set_config(&self, config: u8) -> u8 {
-        const MASK: u8 = 0b00_111111;
+    const fn set_config(&self, config: u16) -> u16 {
+        const MASK: u16 = 0b11111111_00_111111;
         let config = config & MASK;
         config | self.as_config()
     }
@@ -46,18 +50,18 @@ enum Fallback {
 }
 
 impl Fallback {
-    const BIT_SHIFT: u8 = 5;
+    const BIT_SHIFT: u16 = 5;
 
-    const fn as_config(&self) -> u8 {
-        let value: u8 = match self {
+    const fn as_config(&self) -> u16 {
+        let value: u16 = match self {
             Self::Yes => 1,
             Self::No => 0,
         };
         value << Self::BIT_SHIFT
     }
 
-    fn from_config(config: u8) -> Self {
-        const MASK: u8 = 0b00_1_00000;
+    fn from_config(config: u16) -> Self {
+        const MASK: u16 = 0b00000000_00_1_00000;
         match (config & MASK) >> Self::BIT_SHIFT {
             0 => Self::No,
             1 => Self::Yes,
@@ -65,8 +69,8 @@ impl Fallback {
         }
     }
 
-    const fn set_config(&self, config: u8) -> u8 {
-        const MASK: u8 = 0b11_0_11111;
+    const fn set_config(&self, config: u16) -> u16 {
+        const MASK: u16 = 0b11111111_11_0_11111;
         let config = config & MASK;
         config | self.as_config()
     }
@@ -89,18 +93,133 @@ impl From<Fallback> for bool {
     }
 }
 
-const DEFAULT_COLOR_SUPPORT_CONFIG: u8 = ColorSupport::None.as_config();
-const DEFAULT_CONVERT_TO_SUPPORTED_CONFIG: u8 = Fallback::Yes.as_config();
-static CONFIG: AtomicU8 =
-    AtomicU8::new(DEFAULT_COLOR_SUPPORT_CONFIG | DEFAULT_CONVERT_TO_SUPPORTED_CONFIG);
+/// Should padding from the formatter's width be inside of the escape sequences?
+#[derive(Debug)]
+enum Padding {
+    /// The padding is styled like the value.
+    Styled,
+    /// The padding is written outside of all escape sequences.
+    Unstyled,
+}
+
+impl Padding {
+    const BIT_SHIFT: u16 = 4;
+
+    const fn as_config(&self) -> u16 {
+        let value: u16 = match self {
+            Self::Styled => 1,
+            Self::Unstyled => 0,
+        };
+        value << Self::BIT_SHIFT
+    }
+
+    fn from_config(config: u16) -> Self {
+        const MASK: u16 = 0b00000000_00_0_1_0000;
+        match (config & MASK) >> Self::BIT_SHIFT {
+            0 => Self::Unstyled,
+            1 => Self::Styled,
+            _ => unreachable!("Invalid bitwise logic"),
+        }
+    }
+
+    const fn set_config(&self, config: u16) -> u16 {
+        const MASK: u16 = 0b11111111_11_1_0_1111;
+        let config = config & MASK;
+        config | self.as_config()
+    }
+}
+
+impl From<bool> for Padding {
+    #[inline]
+    fn from(value: bool) -> Self {
+        if value { Self::Styled } else { Self::Unstyled }
+    }
+}
+
+impl From<Padding> for bool {
+    #[inline]
+    fn from(value: Padding) -> Self {
+        match value {
+            Padding::Styled => true,
+            Padding::Unstyled => false,
+        }
+    }
+}
+
+impl ColorSyntax {
+    const BIT_SHIFT: u16 = 2;
+
+    const fn as_config(&self) -> u16 {
+        let value: u16 = match self {
+            Self::Semicolon => 0b00,
+            Self::Colon => 0b01,
+            Self::ColonWithColorspace => 0b10,
+        };
+        value << Self::BIT_SHIFT
+    }
+
+    fn from_config(config: u16) -> Self {
+        const MASK: u16 = 0b00000000_00_0_0_11_00;
+        match (config & MASK) >> Self::BIT_SHIFT {
+            0b00 => Self::Semicolon,
+            0b01 => Self::Colon,
+            0b10 => Self::ColonWithColorspace,
+            _ => unreachable!("Invalid bitwise logic"),
+        }
+    }
+
+    const fn set_config(&self, config: u16) -> u16 {
+        const MASK: u16 = 0b11111111_11_1_1_00_11;
+        let config = config & MASK;
+        config | self.as_config()
+    }
+}
+
+impl Capabilities {
+    const BIT_SHIFT: u16 = 8;
+
+    const fn as_config(&self) -> u16 {
+        (self.bits() as u16) << Self::BIT_SHIFT
+    }
+
+    fn from_config(config: u16) -> Self {
+        const MASK: u16 = 0b11111111_00000000;
+        Self::from_bits(((config & MASK) >> Self::BIT_SHIFT) as u8)
+    }
+
+    const fn set_config(&self, config: u16) -> u16 {
+        const MASK: u16 = 0b00000000_11111111;
+        let config = config & MASK;
+        config | self.as_config()
+    }
+}
+
+const DEFAULT_COLOR_SUPPORT_CONFIG: u16 = ColorSupport::None.as_config();
+const DEFAULT_CONVERT_TO_SUPPORTED_CONFIG: u16 = Fallback::Yes.as_config();
+const DEFAULT_PADDING_CONFIG: u16 = Padding::Styled.as_config();
+const DEFAULT_CAPABILITIES_CONFIG: u16 = Capabilities::BASIC.as_config();
+const DEFAULT_COLOR_SYNTAX_CONFIG: u16 = ColorSyntax::Semicolon.as_config();
+static CONFIG: AtomicU16 = AtomicU16::new(
+    DEFAULT_COLOR_SUPPORT_CONFIG
+        | DEFAULT_CONVERT_TO_SUPPORTED_CONFIG
+        | DEFAULT_PADDING_CONFIG
+        | DEFAULT_CAPABILITIES_CONFIG
+        | DEFAULT_COLOR_SYNTAX_CONFIG,
+);
+
+/// The global theme. `None` means that the default theme is used.
+static THEME: RwLock<Option<Theme>> = RwLock::new(None);
+
+/// The global monochrome strategy.
+static MONOCHROME: RwLock<Monochrome> = RwLock::new(Monochrome::Off);
 
 /// Stores the raw config.
-fn store_config(value: u8) {
+fn store_config(value: u16) {
     CONFIG.store(value, ORDERING);
 }
 
 /// Gets the config value.
-fn get_config() -> u8 {
+fn get_config() -> u16 {
     CONFIG.load(ORDERING)
 }
 
@@ -138,6 +257,108 @@ pub fn convert_to_supported(should_convert: bool) {
     store_config(config);
 }
 
+/// Controls if padding from the formatter's width (like `{:>10}`) is styled like the
+/// value. If `true` (the default), the padding is written inside of the escape
+/// sequences, so that background colors extend over it. If `false`, the padding is
+/// written outside of the escape sequences.
+pub fn style_padding(styled: bool) {
+    let config = get_config();
+    let config = Padding::from(styled).set_config(config);
+    store_config(config);
+}
+
+/// Returns if padding from the formatter's width is styled like the value.
+pub fn get_style_padding() -> bool {
+    let config = get_config();
+    Padding::from_config(config).into()
+}
+
+/// Sets the global terminal capabilities. Styles and hyperlinks that require a
+/// capability that isn't in the set are not written. See [`Capabilities`].
+pub fn use_capabilities(capabilities: Capabilities) {
+    let config = get_config();
+    let config = capabilities.set_config(config);
+    store_config(config);
+}
+
+/// Gets the current globally-set terminal capabilities.
+pub fn get_capabilities() -> Capabilities {
+    let config = get_config();
+    Capabilities::from_config(config)
+}
+
+/// Use reasonable defaults for terminal capabilities.
+pub fn use_default_capabilities() {
+    use_capabilities(Capabilities::from_env());
+}
+
+/// Controls if hyperlinks should be written. This is separate from color support,
+/// because terminals with full color support don't necessarily support hyperlinks.
+///
+/// This is a shortcut for adding or removing [`Capabilities::HYPERLINKS`].
+pub fn use_hyperlinks(enabled: bool) {
+    let mut capabilities = get_capabilities();
+    capabilities.set(Capabilities::HYPERLINKS, enabled);
+    use_capabilities(capabilities);
+}
+
+/// Returns if hyperlinks will be written.
+pub fn get_hyperlinks() -> bool {
+    get_capabilities().contains(Capabilities::HYPERLINKS)
+}
+
+/// Use a reasonable default for hyperlink support.
+pub fn use_default_hyperlinks() {
+    use_hyperlinks(crate::link::hyperlinks_from_env());
+}
+
+/// Sets how 8-bit and true colors are written in escape sequences. See
+/// [`ColorSyntax`].
+pub fn use_color_syntax(syntax: ColorSyntax) {
+    let config = get_config();
+    let config = syntax.set_config(config);
+    store_config(config);
+}
+
+/// Gets the current globally-set color syntax.
+pub fn get_color_syntax() -> ColorSyntax {
+    let config = get_config();
+    ColorSyntax::from_config(config)
+}
+
+/// Use a reasonable default for the color syntax.
+pub fn use_default_color_syntax() {
+    use_color_syntax(ColorSyntax::from_env());
+}
+
+/// Sets the global theme used by [`Stylize::role`](crate::Stylize::role).
+pub fn use_theme(theme: Theme) {
+    let mut global = THEME.write().unwrap_or_else(PoisonError::into_inner);
+    *global = Some(theme);
+}
+
+/// Gets the current globally-set theme.
+pub fn get_theme() -> Theme {
+    let global = THEME.read().unwrap_or_else(PoisonError::into_inner);
+    global.unwrap_or_default()
+}
+
+/// Sets how colors should be shown when there is no color support at all. Only used
+/// when converting to supported colors. See [`Monochrome`].
+///
+/// This also applies when there is no color support because of `NO_COLOR` (see
+/// [`ColorSupport::respect_no_color`]), since `NO_COLOR` only asks for no colors, and
+/// still allows styles like bold. Use [`Monochrome::Off`] to write plain text instead.
+pub fn use_monochrome(strategy: Monochrome) {
+    let mut global = MONOCHROME.write().unwrap_or_else(PoisonError::into_inner);
+    *global = strategy;
+}
+
+/// Gets the current globally-set monochrome strategy.
+pub fn get_monochrome() -> Monochrome {
+    *MONOCHROME.read().unwrap_or_else(PoisonError::into_inner)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -145,25 +366,87 @@ mod tests {
     #[test]
     fn test_config() {
         // NOTE This must all be in one test, as running in parallel can cause confusing
-        //      behavior.
-        store_config(0);
+        //      behavior. Settings that other tests rely on (like italics and styled
+        //      padding) are never removed.
+        store_config(DEFAULT_CAPABILITIES_CONFIG | DEFAULT_PADDING_CONFIG);
         use_color_support(ColorSupport::True);
         assert_eq!(
             CONFIG.load(ORDERING),
-            0b11_0_00000,
+            0b00010011_11_0_1_0000,
             "color support should be set to True"
         );
         convert_to_supported(true);
         assert_eq!(
             CONFIG.load(ORDERING),
-            0b11_1_00000,
+            0b00010011_11_1_1_0000,
             "convert to supported bit should be set"
         );
         convert_to_supported(false);
         assert_eq!(
             CONFIG.load(ORDERING),
-            0b11_0_00000,
+            0b00010011_11_0_1_0000,
             "convert to supported bit should be unset"
         );
+        use_color_support(ColorSupport::Simple);
+        assert_eq!(
+            get_capabilities(),
+            Capabilities::BASIC,
+            "capabilities should be unaffected by color support"
+        );
+        assert!(get_style_padding(), "padding should be styled by default");
+        assert_eq!(
+            Padding::from(false).set_config(CONFIG.load(ORDERING)),
+            0b00010011_01_0_0_0000,
+            "padding bit should be unset"
+        );
+    }
+
+    #[test]
+    fn test_capabilities_config() {
+        // NOTE The global config isn't changed, so that other tests aren't affected.
+        let config = 0b00010011_11_1_1_00_00;
+        let capabilities = Capabilities::ITALIC | Capabilities::BLINK;
+        let updated = capabilities.set_config(config);
+        assert_eq!(
+            updated, 0b00010001_11_1_1_00_00,
+            "capabilities should be replaced"
+        );
+        assert_eq!(Capabilities::from_config(updated), capabilities);
+        assert_eq!(
+            ColorSupport::from_config(updated),
+            ColorSupport::True,
+            "color support should be unaffected by capabilities"
+        );
+    }
+
+    #[test]
+    fn test_hyperlinks_config() {
+        // NOTE The global config isn't changed, so that other tests aren't affected.
+        let config = 0b00010011_11_1_1_00_00;
+        let mut capabilities = Capabilities::from_config(config);
+        capabilities.set(Capabilities::HYPERLINKS, true);
+        let updated = capabilities.set_config(config);
+        assert_eq!(
+            updated, 0b00011011_11_1_1_00_00,
+            "hyperlinks capability should be set"
+        );
+        assert!(Capabilities::from_config(updated).contains(Capabilities::HYPERLINKS));
+        capabilities.set(Capabilities::HYPERLINKS, false);
+        assert_eq!(capabilities.set_config(updated), config);
+    }
+
+    #[test]
+    fn test_color_syntax_config() {
+        // NOTE The global config isn't changed, so that other tests aren't affected.
+        let config = 0b00010011_11_1_1_00_00;
+        for syntax in [
+            ColorSyntax::Semicolon,
+            ColorSyntax::Colon,
+            ColorSyntax::ColonWithColorspace,
+        ] {
+            let updated = syntax.set_config(config);
+            assert_eq!(ColorSyntax::from_config(updated), syntax);
+            assert_eq!(updated & !0b1100, config, "other settings should be kept");
+        }
     }
 }
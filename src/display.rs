@@ -1,5 +1,8 @@
 use crate::ColorSupport;
-use std::fmt;
+use crate::ansi::{WidthCounter, visible_width};
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+use std::path;
 /// Like `Display`, but checks for color support. It should disable any coloring or
 /// styling that isn't supported.
 pub trait DisplayWithExact {
@@ -21,6 +24,35 @@ pub trait DisplayWithExact {
             support: supported,
         }
     }
+
+    /// Gets the number of terminal columns the value takes up when it's displayed.
+    /// Escape sequences don't count, and wide characters, like CJK characters and
+    /// emoji, count as 2 columns. The value is measured without being rendered to a
+    /// `String`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use chromaterm::prelude::*;
+    ///
+    /// assert_eq!("\u{4F60}\u{597D}".red().bold().visible_width(), 4);
+    /// assert_eq!("\x1B[31malready red\x1B[0m".visible_width(), 11);
+    /// ```
+    fn visible_width(&self) -> usize {
+        let mut counter = WidthCounter::new();
+        let render = FmtFn(|f: &mut fmt::Formatter<'_>| self.fmt_exact(ColorSupport::None, f));
+        // NOTE The counter never fails, so neither can writing to it.
+        let _ = write!(counter, "{render}");
+        counter.width()
+    }
+
+    /// Gets the alignment that is used for padding if the formatter doesn't have one.
+    /// Like with std, numbers are aligned to the right, and everything else to the
+    /// left.
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        fmt::Alignment::Left
+    }
 }
 
 /// Like `Display`, but checks for color support. If the coloring or styling isn't
@@ -44,6 +76,14 @@ pub trait DisplayWithFallback {
             support: supported,
         }
     }
+
+    /// Gets the alignment that is used for padding if the formatter doesn't have one.
+    /// Like with std, numbers are aligned to the right, and everything else to the
+    /// left.
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        fmt::Alignment::Left
+    }
 }
 
 impl DisplayWithExact for str {
@@ -54,7 +94,7 @@ impl DisplayWithExact for str {
         _supported: ColorSupport,
         f: &mut fmt::Formatter<'_>,
     ) -> Result<(), fmt::Error> {
-        fmt::Display::fmt(self, f)
+        fmt_plain(self, f)
     }
 }
 
@@ -66,31 +106,157 @@ impl DisplayWithFallback for str {
         _supported: ColorSupport,
         f: &mut fmt::Formatter<'_>,
     ) -> Result<(), fmt::Error> {
-        fmt::Display::fmt(self, f)
+        fmt_plain(self, f)
     }
 }
 
-impl DisplayWithExact for &str {
-    /// Formats the value, never checking for color support, because it is assumed
-    /// that a raw `str` does not need to check for color support.
+impl<D: DisplayWithExact + ?Sized> DisplayWithExact for &D {
+    #[inline]
+    fn fmt_exact(
+        &self,
+        supported: ColorSupport,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        (**self).fmt_exact(supported, f)
+    }
+
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        (**self).default_alignment()
+    }
+}
+
+impl<D: DisplayWithFallback + ?Sized> DisplayWithFallback for &D {
+    #[inline]
+    fn fmt_fallback(
+        &self,
+        supported: ColorSupport,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        (**self).fmt_fallback(supported, f)
+    }
+
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        (**self).default_alignment()
+    }
+}
+
+/// Implements the display traits for types that are displayed without any color,
+/// using `$fmt` to format the value.
+macro_rules! impl_display_with_plain {
+    ($fmt:path, $alignment:expr; $($t:ty),+ $(,)?) => {
+        $(
+            impl DisplayWithExact for $t {
+                /// Formats the value, never checking for color support, because the
+                /// value itself is never colored.
+                fn fmt_exact(
+                    &self,
+                    _supported: ColorSupport,
+                    f: &mut fmt::Formatter<'_>,
+                ) -> Result<(), fmt::Error> {
+                    $fmt(self, f)
+                }
+
+                #[inline]
+                fn default_alignment(&self) -> fmt::Alignment {
+                    $alignment
+                }
+            }
+
+            impl DisplayWithFallback for $t {
+                /// Formats the value, never checking for color support, because the
+                /// value itself is never colored.
+                fn fmt_fallback(
+                    &self,
+                    _supported: ColorSupport,
+                    f: &mut fmt::Formatter<'_>,
+                ) -> Result<(), fmt::Error> {
+                    $fmt(self, f)
+                }
+
+                #[inline]
+                fn default_alignment(&self) -> fmt::Alignment {
+                    $alignment
+                }
+            }
+        )+
+    };
+}
+
+impl_display_with_plain!(
+    fmt::Display::fmt, fmt::Alignment::Right;
+    i8, i16, i32, i64, i128, isize,
+    u8, u16, u32, u64, u128, usize,
+    f32, f64,
+);
+
+impl_display_with_plain!(fmt::Display::fmt, fmt::Alignment::Left; bool);
+
+// NOTE These types either ignore the formatter's width, or pad by counting characters
+//      instead of columns, so they're padded manually.
+impl_display_with_plain!(
+    fmt_plain, fmt::Alignment::Left;
+    char,
+    String,
+    Cow<'_, str>,
+    fmt::Arguments<'_>,
+    path::Display<'_>,
+);
+
+/// Formats a value, padding it to the formatter's width even if the value ignores the
+/// width.
+#[inline]
+pub(crate) fn fmt_plain<D: fmt::Display + ?Sized>(
+    value: &D,
+    f: &mut fmt::Formatter<'_>,
+) -> Result<(), fmt::Error> {
+    match f.width() {
+        Some(width) => pad(value, width, fmt::Alignment::Left, f),
+        None => value.fmt(f),
+    }
+}
+
+/// Wraps any [`Display`](fmt::Display) value so that it can be colored and styled
+/// without converting it to a `String` first.
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::prelude::*;
+/// use chromaterm::{ColorSupport, Plain};
+/// use std::net::Ipv4Addr;
+///
+/// let address = Plain(Ipv4Addr::LOCALHOST).red();
+/// assert_eq!(
+///     address.display_exact(ColorSupport::Simple).to_string(),
+///     "\x1B[31m127.0.0.1\x1B[0m",
+/// );
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Plain<T>(pub T);
+
+impl<T: fmt::Display> DisplayWithExact for Plain<T> {
+    /// Formats the value, never checking for color support, because the value itself
+    /// is never colored.
     fn fmt_exact(
         &self,
         _supported: ColorSupport,
         f: &mut fmt::Formatter<'_>,
     ) -> Result<(), fmt::Error> {
-        fmt::Display::fmt(self, f)
+        fmt_plain(&self.0, f)
     }
 }
 
-impl DisplayWithFallback for &str {
-    /// Formats the value, never checking for color support, because it is assumed
-    /// that a raw `str` does not need to check for color support.
+impl<T: fmt::Display> DisplayWithFallback for Plain<T> {
+    /// Formats the value, never checking for color support, because the value itself
+    /// is never colored.
     fn fmt_fallback(
         &self,
         _supported: ColorSupport,
         f: &mut fmt::Formatter<'_>,
     ) -> Result<(), fmt::Error> {
-        fmt::Display::fmt(self, f)
+        fmt_plain(&self.0, f)
     }
 }
 
@@ -103,7 +269,9 @@ pub struct DisplayExact<D: DisplayWithExact> {
 
 impl<D: DisplayWithExact> fmt::Display for DisplayExact<D> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        self.inner.fmt_exact(self.support, f)
+        let render = FmtFn(|f: &mut fmt::Formatter<'_>| self.inner.fmt_exact(self.support, f));
+        let alignment = self.inner.default_alignment();
+        fmt_padded(&render, alignment, crate::config::get_style_padding(), f)
     }
 }
 
@@ -116,6 +284,300 @@ pub struct DisplayFallback<D: DisplayWithFallback> {
 
 impl<D: DisplayWithFallback> fmt::Display for DisplayFallback<D> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        self.inner.fmt_fallback(self.support, f)
+        let render = FmtFn(|f: &mut fmt::Formatter<'_>| self.inner.fmt_fallback(self.support, f));
+        let alignment = self.inner.default_alignment();
+        fmt_padded(&render, alignment, crate::config::get_style_padding(), f)
+    }
+}
+
+/// Displays the value using the globally-set configuration. This is the shared
+/// implementation of `Display` for all colorized and styled values.
+pub(crate) fn fmt_configured<D: DisplayWithExact + DisplayWithFallback>(
+    value: &D,
+    f: &mut fmt::Formatter<'_>,
+) -> Result<(), fmt::Error> {
+    let support = crate::config::get_color_support();
+    let should_fallback = crate::config::get_convert_to_supported();
+    let render = FmtFn(|f: &mut fmt::Formatter<'_>| {
+        if should_fallback {
+            value.fmt_fallback(support, f)
+        } else {
+            value.fmt_exact(support, f)
+        }
+    });
+    let alignment = DisplayWithExact::default_alignment(value);
+    fmt_padded(&render, alignment, crate::config::get_style_padding(), f)
+}
+
+/// Adapts a formatting function to `Display`.
+pub(crate) struct FmtFn<F: Fn(&mut fmt::Formatter<'_>) -> Result<(), fmt::Error>>(pub(crate) F);
+
+impl<F: Fn(&mut fmt::Formatter<'_>) -> Result<(), fmt::Error>> fmt::Display for FmtFn<F> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        (self.0)(f)
+    }
+}
+
+/// Displays a colorized or styled value, honoring the formatter's width, fill, and
+/// alignment. The value is aligned with `alignment` if the formatter doesn't have an
+/// alignment.
+///
+/// If the padding is `styled`, the formatter is passed through every wrapper to the
+/// innermost value, which pads itself inside of all escape sequences. Otherwise, the
+/// padding is written outside of all escape sequences. Zero padding goes between the
+/// sign and the digits, so it's always written by the innermost value.
+///
+/// The precision and the `+` and `#` flags are always passed through to the innermost
+/// value.
+fn fmt_padded<D: fmt::Display>(
+    value: &D,
+    alignment: fmt::Alignment,
+    styled: bool,
+    f: &mut fmt::Formatter<'_>,
+) -> Result<(), fmt::Error> {
+    match f.width() {
+        Some(width) if !styled && !f.sign_aware_zero_pad() => pad(value, width, alignment, f),
+        _ => value.fmt(f),
+    }
+}
+
+/// Writes the value, padded to the width with the formatter's fill and alignment, or
+/// with `alignment` if the formatter doesn't have one. Escape sequences don't count
+/// towards the width, and wide characters count as 2 columns.
+///
+/// The value is only formatted once. Unless the padding is only after the value, the
+/// value is formatted into a buffer, so that it's measured before the padding.
+fn pad<D: fmt::Display + ?Sized>(
+    value: &D,
+    width: usize,
+    alignment: fmt::Alignment,
+    f: &mut fmt::Formatter<'_>,
+) -> Result<(), fmt::Error> {
+    let flags = Flags::of(f);
+    let alignment = f.align().unwrap_or(alignment);
+    let fill = f.fill();
+    if alignment == fmt::Alignment::Left {
+        let mut writer = CountingWriter {
+            inner: &mut *f,
+            counter: WidthCounter::new(),
+        };
+        flags.write(value, &mut writer)?;
+        let padding = width.saturating_sub(writer.counter.width());
+        return Padding::new(padding, fill, alignment).write_after(f);
+    }
+    let mut buffer = String::new();
+    flags.write(value, &mut buffer)?;
+    let padding = width.saturating_sub(visible_width(&buffer));
+    let padding = Padding::new(padding, fill, alignment);
+    padding.write_before(f)?;
+    f.write_str(&buffer)?;
+    padding.write_after(f)
+}
+
+/// The fill characters that pad a value to a width.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Padding {
+    pub(crate) fill: char,
+    /// The number of fill characters before the value.
+    pub(crate) before: usize,
+    /// The number of fill characters after the value.
+    pub(crate) after: usize,
+}
+
+impl Padding {
+    /// Splits the number of fill characters into the ones before and after the value.
+    pub(crate) fn new(padding: usize, fill: char, alignment: fmt::Alignment) -> Self {
+        let (before, after) = match alignment {
+            fmt::Alignment::Left => (0, padding),
+            fmt::Alignment::Right => (padding, 0),
+            fmt::Alignment::Center => (padding / 2, padding - (padding / 2)),
+        };
+        Self {
+            fill,
+            before,
+            after,
+        }
+    }
+
+    /// Writes the fill characters before the value.
+    pub(crate) fn write_before<W: Write + ?Sized>(&self, w: &mut W) -> Result<(), fmt::Error> {
+        (0..self.before).try_for_each(|_| w.write_char(self.fill))
+    }
+
+    /// Writes the fill characters after the value.
+    pub(crate) fn write_after<W: Write + ?Sized>(&self, w: &mut W) -> Result<(), fmt::Error> {
+        (0..self.after).try_for_each(|_| w.write_char(self.fill))
+    }
+}
+
+/// The formatter's flags that are passed through to a value that is padded separately.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Flags {
+    sign_plus: bool,
+    alternate: bool,
+    precision: Option<usize>,
+}
+
+impl Flags {
+    /// Gets the flags of the formatter.
+    pub(crate) fn of(f: &fmt::Formatter<'_>) -> Self {
+        Self {
+            sign_plus: f.sign_plus(),
+            alternate: f.alternate(),
+            precision: f.precision(),
+        }
+    }
+
+    /// Writes the value with the flags, but without any width.
+    pub(crate) fn write<D: fmt::Display + ?Sized, W: Write>(
+        self,
+        value: &D,
+        w: &mut W,
+    ) -> Result<(), fmt::Error> {
+        match (self.sign_plus, self.alternate, self.precision) {
+            (false, false, None) => write!(w, "{value}"),
+            (true, false, None) => write!(w, "{value:+}"),
+            (false, true, None) => write!(w, "{value:#}"),
+            (true, true, None) => write!(w, "{value:+#}"),
+            (false, false, Some(precision)) => write!(w, "{value:.precision$}"),
+            (true, false, Some(precision)) => write!(w, "{value:+.precision$}"),
+            (false, true, Some(precision)) => write!(w, "{value:#.precision$}"),
+            (true, true, Some(precision)) => write!(w, "{value:+#.precision$}"),
+        }
+    }
+}
+
+/// Passes everything through to a writer, counting the columns of the text.
+struct CountingWriter<'a, W: ?Sized> {
+    inner: &'a mut W,
+    counter: WidthCounter,
+}
+
+impl<W: Write + ?Sized> Write for CountingWriter<'_, W> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
+        self.counter.count(s);
+        self.inner.write_str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Colorize, Stylize};
+    use rstest::rstest;
+    use std::path::Path;
+
+    #[rstest]
+    #[case(42u8.red(), "\x1B[31m42\x1B[0m")]
+    #[case(1.5f64.green(), "\x1B[32m1.5\x1B[0m")]
+    #[case('x'.bold(), "\x1B[1mx\x1B[0m")]
+    #[case(true.blue(), "\x1B[34mtrue\x1B[0m")]
+    #[case(String::from("owned").red(), "\x1B[31mowned\x1B[0m")]
+    #[case(Cow::Borrowed("cow").red(), "\x1B[31mcow\x1B[0m")]
+    #[case(Path::new("a/b").display().red(), "\x1B[31ma/b\x1B[0m")]
+    fn test_plain_values<D: DisplayWithExact>(#[case] value: D, #[case] expected: &str) {
+        assert_eq!(
+            value.display_exact(ColorSupport::Simple).to_string(),
+            expected
+        );
+    }
+
+    /// Helper to display with explicitly styled or unstyled padding.
+    struct Padded<D>(D, bool);
+
+    impl<D: DisplayWithExact> fmt::Display for Padded<D> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+            let render =
+                FmtFn(|f: &mut fmt::Formatter<'_>| self.0.fmt_exact(ColorSupport::Simple, f));
+            fmt_padded(&render, self.0.default_alignment(), self.1, f)
+        }
+    }
+
+    #[rstest]
+    #[case(true, "\x1B[31mok   \x1B[0m")]
+    #[case(false, "\x1B[31mok\x1B[0m   ")]
+    fn test_padding_left(#[case] styled: bool, #[case] expected: &str) {
+        let value = Padded("ok".red(), styled);
+        assert_eq!(format!("{value:5}"), expected);
+    }
+
+    #[rstest]
+    #[case(true, "\x1B[31m\u{4F60}\u{597D}  \x1B[0m")]
+    #[case(false, "\x1B[31m\u{4F60}\u{597D}\x1B[0m  ")]
+    fn test_padding_wide_characters(#[case] styled: bool, #[case] expected: &str) {
+        let value = Padded("\u{4F60}\u{597D}".red(), styled);
+        assert_eq!(format!("{value:6}"), expected);
+    }
+
+    #[rstest]
+    #[case(true, "\x1B[1m\x1B[31m---ok\x1B[0m\x1B[0m")]
+    #[case(false, "---\x1B[1m\x1B[31mok\x1B[0m\x1B[0m")]
+    fn test_padding_right(#[case] styled: bool, #[case] expected: &str) {
+        let value = Padded("ok".red().bold(), styled);
+        assert_eq!(format!("{value:->5}"), expected);
+    }
+
+    #[rstest]
+    #[case(true, "\x1B[31m.abc..\x1B[0m")]
+    #[case(false, ".\x1B[31mabc\x1B[0m..")]
+    fn test_padding_center_with_precision(#[case] styled: bool, #[case] expected: &str) {
+        let value = Padded("abcdef".red(), styled);
+        assert_eq!(format!("{value:.^6.3}"), expected);
+    }
+
+    #[rstest]
+    #[case(true, "\x1B[31m1 items  \x1B[0m")]
+    #[case(false, "\x1B[31m1 items\x1B[0m  ")]
+    fn test_padding_ignored_width(#[case] styled: bool, #[case] expected: &str) {
+        /// Displays without checking the formatter's width.
+        struct IgnoresWidth;
+
+        impl fmt::Display for IgnoresWidth {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+                f.write_str("1 items")
+            }
+        }
+
+        let value = Padded(Plain(IgnoresWidth).red(), styled);
+        assert_eq!(format!("{value:9}"), expected);
+    }
+
+    #[rstest]
+    #[case(true, |v: &dyn fmt::Display| format!("{v:5}"), "\x1B[31m   42\x1B[0m")]
+    #[case(false, |v: &dyn fmt::Display| format!("{v:5}"), "   \x1B[31m42\x1B[0m")]
+    #[case(false, |v: &dyn fmt::Display| format!("{v:<5}"), "\x1B[31m42\x1B[0m   ")]
+    #[case(false, |v: &dyn fmt::Display| format!("{v:^6}"), "  \x1B[31m42\x1B[0m  ")]
+    #[case(true, |v: &dyn fmt::Display| format!("{v:05}"), "\x1B[31m00042\x1B[0m")]
+    #[case(false, |v: &dyn fmt::Display| format!("{v:05}"), "\x1B[31m00042\x1B[0m")]
+    #[case(false, |v: &dyn fmt::Display| format!("{v:+5}"), "  \x1B[31m+42\x1B[0m")]
+    #[case(false, |v: &dyn fmt::Display| format!("{v:+}"), "\x1B[31m+42\x1B[0m")]
+    fn test_padding_numbers(
+        #[case] styled: bool,
+        #[case] format: fn(&dyn fmt::Display) -> String,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(format(&Padded(42.red(), styled)), expected);
+    }
+
+    #[rstest]
+    #[case(true, "\x1B[31m  +1.50\x1B[0m")]
+    #[case(false, "  \x1B[31m+1.50\x1B[0m")]
+    fn test_padding_float_flags(#[case] styled: bool, #[case] expected: &str) {
+        let value = Padded(1.5f64.red(), styled);
+        assert_eq!(format!("{value:+7.2}"), expected);
+    }
+
+    #[test]
+    fn test_arguments() {
+        let n = 3;
+        assert_eq!(
+            format_args!("{n} items")
+                .red()
+                .display_exact(ColorSupport::Simple)
+                .to_string(),
+            "\x1B[31m3 items\x1B[0m"
+        );
     }
 }
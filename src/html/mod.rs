@@ -0,0 +1,516 @@
+//! Rendering colorized and styled values as HTML.
+//!
+//! # Example
+//!
+//! ```rust
+//! use chromaterm::prelude::*;
+//! use chromaterm::ColorSupport;
+//!
+//! let value = "<tag>".red().bold();
+//! assert_eq!(
+//!     (&value).display_html(ColorSupport::True).to_string(),
+//!     "<span style=\"font-weight:bold\"><span style=\"color:#aa0000\">&lt;tag&gt;</span></span>",
+//! );
+//! assert_eq!(
+//!     value.display_html(ColorSupport::True).with_classes().to_string(),
+//!     "<span class=\"ct-bold\"><span class=\"ct-fg-red\">&lt;tag&gt;</span></span>",
+//! );
+//! ```
+use crate::backend::{Attr, Backend, DisplayWithBackend};
+use crate::colors::{Palette, Simple};
+use crate::styles::StyleSet;
+use crate::{ColorSupport, Colors, Spec, Styles};
+use std::fmt::{self, Write};
+pub use writer::{HtmlWriter, from_ansi};
+
+mod writer;
+
+/// Like [`DisplayWithFallback`](crate::DisplayWithFallback), but writes HTML instead of
+/// escape sequences.
+///
+/// Colors are converted to colors that are supported, and styles are shown if there
+/// is *any* color support. Capabilities are ignored, because browsers can show every
+/// style. Text is escaped, so it's safe to include in an HTML document.
+///
+/// This is implemented for every type that implements [`DisplayWithBackend`], with
+/// [`Html`] as the backend.
+pub trait DisplayWithHtml {
+    /// Formats the value as HTML.
+    fn fmt_html(&self, html: &Html, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error>;
+
+    /// Converts to a type that displays the value as HTML.
+    fn display_html(self, supported: ColorSupport) -> DisplayHtml<Self>
+    where
+        Self: Sized,
+    {
+        DisplayHtml {
+            inner: self,
+            html: Html::new(supported),
+        }
+    }
+}
+
+/// Settings for rendering HTML, and the [`Backend`] that writes HTML.
+///
+/// With inline styles, reversed text is written with its text and background colors
+/// swapped. A missing text color is taken to be the palette's white, and a missing
+/// background color the palette's black, like in most terminals. Hyperlinks are only
+/// written if their scheme is `http`, `https`, `mailto`, or `file`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Html {
+    support: ColorSupport,
+    classes: bool,
+    palette: Palette,
+    /// For each open group, if a `<span>` was written, and the colors and styles that
+    /// apply inside of it.
+    open: Vec<(bool, Spec)>,
+}
+
+impl Html {
+    /// Creates settings that render colors that are supported with inline styles, and
+    /// the default [`Palette`].
+    #[inline]
+    pub fn new(support: ColorSupport) -> Self {
+        Self {
+            support,
+            classes: false,
+            palette: Palette::default(),
+            open: Vec::new(),
+        }
+    }
+
+    /// Sets whether CSS classes should be used instead of inline styles.
+    ///
+    /// Classes are prefixed with `ct-`. Styles use their name, like `ct-bold` and
+    /// `ct-curly-underline`. Simple colors are named, like `ct-fg-red` and
+    /// `ct-bg-bright-black`, and 8-bit colors use their number, like `ct-fg-208`. True
+    /// colors can't be named, so they always use inline styles.
+    #[inline]
+    pub const fn with_classes(mut self, classes: bool) -> Self {
+        self.classes = classes;
+        self
+    }
+
+    /// Sets the palette that the simple colors are shown with when using inline styles.
+    #[inline]
+    pub const fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Gets the color support.
+    #[inline]
+    pub const fn support(&self) -> ColorSupport {
+        self.support
+    }
+
+    /// Checks if CSS classes are used instead of inline styles.
+    #[inline]
+    pub const fn classes(&self) -> bool {
+        self.classes
+    }
+
+    /// Gets the palette.
+    #[inline]
+    pub const fn palette(&self) -> &Palette {
+        &self.palette
+    }
+
+    /// Writes a `<span>` start tag with the colors and styles of the spec, if there's
+    /// anything to show. `active` has all colors and styles that apply inside of the
+    /// `<span>`, including the spec's, and is used to reverse the colors.
+    ///
+    /// Returns `true` if anything was written, meaning that the caller should close the
+    /// `<span>` afterwards.
+    pub(crate) fn write_open<W: Write>(
+        &self,
+        spec: &Spec,
+        active: &Spec,
+        w: &mut W,
+    ) -> Result<bool, fmt::Error> {
+        let styles = if self.support > ColorSupport::None {
+            spec.styles()
+        } else {
+            StyleSet::new()
+        };
+        let fallback = |color: Colors| color.fallback(self.support);
+        // NOTE Reversed colors are swapped with inline styles, and the swapped colors
+        //      are written again if the span changes them.
+        let is_reversed = !self.classes
+            && self.support > ColorSupport::None
+            && active.styles().contains(Styles::Reverse)
+            && (styles.contains(Styles::Reverse) || spec.fg().is_some() || spec.bg().is_some());
+        let (fg, bg) = if is_reversed {
+            let fg = active
+                .bg()
+                .map_or(Some(Colors::Simple(Simple::Black)), fallback);
+            let bg = active
+                .fg()
+                .map_or(Some(Colors::Simple(Simple::White)), fallback);
+            (fg, bg)
+        } else {
+            (spec.fg().and_then(fallback), spec.bg().and_then(fallback))
+        };
+        let colors = [("fg", "color", fg), ("bg", "background-color", bg)];
+        let mut span = SpanWriter::new(w);
+        if self.classes {
+            for style in styles.iter() {
+                span.class(style_class(style))?;
+            }
+            for (prefix, _, color) in colors {
+                match color {
+                    Some(Colors::Simple(simple)) => {
+                        span.class(format_args!("ct-{prefix}-{}", simple_name(simple)))?;
+                    }
+                    Some(Colors::EightBit(eight_bit)) => {
+                        span.class(format_args!("ct-{prefix}-{}", eight_bit.lookup()))?;
+                    }
+                    Some(Colors::True(_)) | None => {}
+                }
+            }
+        } else {
+            for style in styles.iter() {
+                if let Css::Property(property) = style_css(style) {
+                    span.style(property)?;
+                }
+            }
+            let mut has_decoration = false;
+            for style in styles.iter() {
+                let Css::Decoration(decoration) = style_css(style) else {
+                    continue;
+                };
+                if has_decoration {
+                    span.style_part(" ", decoration)?;
+                } else {
+                    span.style(format_args!("text-decoration:{decoration}"))?;
+                    has_decoration = true;
+                }
+            }
+        }
+        for (_, property, color) in colors {
+            let Some(color) = color else {
+                continue;
+            };
+            if self.classes && !matches!(color, Colors::True(_)) {
+                continue;
+            }
+            let (r, g, b) = self.palette.rgb(&color);
+            span.style(format_args!("{property}:#{r:02x}{g:02x}{b:02x}"))?;
+        }
+        span.finish()
+    }
+}
+
+/// Writes the attributes of a `<span>` start tag, starting the tag before the first
+/// one. Classes must be written before styles.
+struct SpanWriter<'a, W: Write> {
+    w: &'a mut W,
+    state: SpanState,
+}
+
+/// The attribute of a `<span>` start tag that is being written.
+#[derive(PartialEq, Eq)]
+enum SpanState {
+    /// Nothing was written yet.
+    Empty,
+    /// In the `class` attribute.
+    Class,
+    /// In the `style` attribute.
+    Style,
+}
+
+impl<'a, W: Write> SpanWriter<'a, W> {
+    /// Wraps the writer.
+    fn new(w: &'a mut W) -> Self {
+        Self {
+            w,
+            state: SpanState::Empty,
+        }
+    }
+
+    /// Writes a CSS class.
+    fn class<D: fmt::Display>(&mut self, class: D) -> Result<(), fmt::Error> {
+        match self.state {
+            SpanState::Empty => write!(self.w, "<span class=\"{class}")?,
+            SpanState::Class | SpanState::Style => write!(self.w, " {class}")?,
+        }
+        self.state = SpanState::Class;
+        Ok(())
+    }
+
+    /// Writes a CSS property.
+    fn style<D: fmt::Display>(&mut self, property: D) -> Result<(), fmt::Error> {
+        self.style_part(";", property)
+    }
+
+    /// Writes part of a CSS property, after the separator if the style attribute was
+    /// already started. Continues the last property if the separator isn't `;`.
+    fn style_part<D: fmt::Display>(&mut self, separator: &str, part: D) -> Result<(), fmt::Error> {
+        let separator = match self.state {
+            SpanState::Empty => "<span style=\"",
+            SpanState::Class => "\" style=\"",
+            SpanState::Style => separator,
+        };
+        write!(self.w, "{separator}{part}")?;
+        self.state = SpanState::Style;
+        Ok(())
+    }
+
+    /// Ends the start tag. Returns `true` if anything was written.
+    fn finish(self) -> Result<bool, fmt::Error> {
+        if self.state == SpanState::Empty {
+            return Ok(false);
+        }
+        self.w.write_str("\">")?;
+        Ok(true)
+    }
+}
+
+impl Backend for Html {
+    /// Writes an `<a>` start tag for a hyperlink, and a `<span>` start tag for the
+    /// colors and styles.
+    fn open(&mut self, attrs: &[Attr<'_>], f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        for url in safe_links(attrs) {
+            f.write_str("<a href=\"")?;
+            Escaper(&mut *f).write_str(url)?;
+            f.write_str("\">")?;
+        }
+        let inherited = self
+            .open
+            .last()
+            .map_or_else(Spec::new, |&(_, active)| active);
+        let active = inherited.with_attrs(attrs);
+        let is_open = self.write_open(&Spec::from_attrs(attrs), &active, f)?;
+        self.open.push((is_open, active));
+        Ok(())
+    }
+
+    /// Writes the end tags that match [`open`](Self::open).
+    fn close(&mut self, attrs: &[Attr<'_>], f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if self.open.pop().is_some_and(|(is_open, _)| is_open) {
+            f.write_str("</span>")?;
+        }
+        for _ in safe_links(attrs) {
+            f.write_str("</a>")?;
+        }
+        Ok(())
+    }
+
+    /// Writes the escaped text.
+    fn text(&mut self, text: &str, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        Escaper(f).write_str(text)
+    }
+}
+
+/// A type suitable for displaying values as HTML. See [`DisplayWithHtml`].
+pub struct DisplayHtml<D: DisplayWithHtml> {
+    inner: D,
+    html: Html,
+}
+
+impl<D: DisplayWithHtml> DisplayHtml<D> {
+    /// Uses CSS classes instead of inline styles. See [`Html::with_classes`].
+    pub fn with_classes(mut self) -> Self {
+        self.html = self.html.with_classes(true);
+        self
+    }
+}
+
+impl<D: DisplayWithHtml> fmt::Display for DisplayHtml<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        self.inner.fmt_html(&self.html, f)
+    }
+}
+
+impl<D: DisplayWithBackend + ?Sized> DisplayWithHtml for D {
+    #[inline]
+    fn fmt_html(&self, html: &Html, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let mut html = html.clone();
+        self.fmt_backend(&mut html, f)
+    }
+}
+
+/// Checks if a hyperlink's URL can be written into an `href`. Only URLs with a scheme
+/// that can't run scripts, like `https`, are allowed, because the URL may come from
+/// untrusted input.
+pub(crate) fn is_safe_url(url: &str) -> bool {
+    let Some((scheme, _)) = url.split_once(':') else {
+        return false;
+    };
+    ["http", "https", "mailto", "file"]
+        .into_iter()
+        .any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+}
+
+/// Gets the URLs of the hyperlinks that can be written into an `href`.
+fn safe_links<'a>(attrs: &'a [Attr<'a>]) -> impl Iterator<Item = &'a str> {
+    attrs.iter().filter_map(|attr| match *attr {
+        Attr::Link(url) if is_safe_url(url) => Some(url),
+        Attr::Fg(_) | Attr::Bg(_) | Attr::Style(_) | Attr::Link(_) => None,
+    })
+}
+
+/// Escapes text for HTML while writing it.
+pub(crate) struct Escaper<W: Write>(pub(crate) W);
+
+impl<W: Write> Write for Escaper<W> {
+    fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
+        let mut rest = s;
+        while let Some(index) = rest.find(['&', '<', '>', '"', '\'']) {
+            self.0.write_str(&rest[..index])?;
+            let escaped = match rest.as_bytes()[index] {
+                b'&' => "&amp;",
+                b'<' => "&lt;",
+                b'>' => "&gt;",
+                b'"' => "&quot;",
+                _ => "&#39;",
+            };
+            self.0.write_str(escaped)?;
+            rest = &rest[index + 1..];
+        }
+        self.0.write_str(rest)
+    }
+}
+
+/// How a style is written as CSS.
+enum Css {
+    /// A complete property.
+    Property(&'static str),
+    /// A value of `text-decoration`, which must be combined with other decorations.
+    Decoration(&'static str),
+    /// The text and background colors are swapped.
+    SwapColors,
+}
+
+/// Gets the CSS for a style.
+fn style_css(style: Styles) -> Css {
+    match style {
+        Styles::Bold => Css::Property("font-weight:bold"),
+        Styles::Dim => Css::Property("opacity:0.5"),
+        Styles::Italic => Css::Property("font-style:italic"),
+        Styles::Underline => Css::Decoration("underline"),
+        Styles::Strike => Css::Decoration("line-through"),
+        Styles::Reverse => Css::SwapColors,
+        Styles::Blink => Css::Decoration("blink"),
+        Styles::CurlyUnderline => Css::Decoration("underline wavy"),
+        Styles::Hidden => Css::Property("visibility:hidden"),
+    }
+}
+
+/// Gets the CSS class for a style.
+fn style_class(style: Styles) -> &'static str {
+    match style {
+        Styles::Bold => "ct-bold",
+        Styles::Dim => "ct-dim",
+        Styles::Italic => "ct-italic",
+        Styles::Underline => "ct-underline",
+        Styles::Strike => "ct-strike",
+        Styles::Reverse => "ct-reverse",
+        Styles::Blink => "ct-blink",
+        Styles::CurlyUnderline => "ct-curly-underline",
+        Styles::Hidden => "ct-hidden",
+    }
+}
+
+/// Gets the name of a simple color, as it's used in CSS classes.
+fn simple_name(color: Simple) -> &'static str {
+    match color {
+        Simple::Black => "black",
+        Simple::Red => "red",
+        Simple::Green => "green",
+        Simple::Yellow => "yellow",
+        Simple::Blue => "blue",
+        Simple::Magenta => "magenta",
+        Simple::Cyan => "cyan",
+        Simple::White => "white",
+        Simple::BrightBlack => "bright-black",
+        Simple::BrightRed => "bright-red",
+        Simple::BrightGreen => "bright-green",
+        Simple::BrightYellow => "bright-yellow",
+        Simple::BrightBlue => "bright-blue",
+        Simple::BrightMagenta => "bright-magenta",
+        Simple::BrightCyan => "bright-cyan",
+        Simple::BrightWhite => "bright-white",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Colorize, Stylize};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("a & b", "a &amp; b")]
+    #[case("<b>\"hi\"</b>", "&lt;b&gt;&quot;hi&quot;&lt;/b&gt;")]
+    #[case("it's", "it&#39;s")]
+    fn test_escaping(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(text.display_html(ColorSupport::True).to_string(), expected);
+    }
+
+    #[rstest]
+    #[case(ColorSupport::None, false, "text")]
+    #[case(
+        ColorSupport::True,
+        false,
+        "<span style=\"font-weight:bold;text-decoration:underline line-through;color:#0a141e\">text</span>"
+    )]
+    #[case(
+        ColorSupport::Simple,
+        true,
+        "<span class=\"ct-bold ct-underline ct-strike ct-fg-black\">text</span>"
+    )]
+    #[case(
+        ColorSupport::True,
+        true,
+        "<span class=\"ct-bold ct-underline ct-strike\" style=\"color:#0a141e\">text</span>"
+    )]
+    fn test_styled(#[case] support: ColorSupport, #[case] classes: bool, #[case] expected: &str) {
+        let value = "text".styled().bold().underline().strike().rgb(10, 20, 30);
+        let html = Html::new(support).with_classes(classes);
+        let display = crate::display::FmtFn(|f: &mut fmt::Formatter<'_>| value.fmt_html(&html, f));
+        assert_eq!(display.to_string(), expected);
+    }
+
+    #[test]
+    fn test_nested() {
+        let value = "x"
+            .on_eight_bit(208)
+            .italic()
+            .link("https://example.com/?a=1&b=2");
+        assert_eq!(
+            value
+                .display_html(ColorSupport::EightBit)
+                .with_classes()
+                .to_string(),
+            "<a href=\"https://example.com/?a=1&amp;b=2\"><span class=\"ct-italic\"><span class=\"ct-bg-208\">x</span></span></a>",
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "x".styled().reverse().red().on_blue(),
+        "<span style=\"color:#0000aa;background-color:#aa0000\">x</span>"
+    )]
+    #[case(
+        "x".styled().reverse(),
+        "<span style=\"color:#000000;background-color:#aaaaaa\">x</span>"
+    )]
+    #[case(
+        "x".red().reverse(),
+        "<span style=\"color:#000000;background-color:#aaaaaa\">\
+         <span style=\"color:#000000;background-color:#aa0000\">x</span></span>"
+    )]
+    fn test_reverse<D: DisplayWithHtml>(#[case] value: D, #[case] expected: &str) {
+        assert_eq!(value.display_html(ColorSupport::True).to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("javascript:alert(1)", "x")]
+    #[case("data:text/html,x", "x")]
+    #[case("https://example.com", "<a href=\"https://example.com\">x</a>")]
+    fn test_link_schemes(#[case] url: &str, #[case] expected: &str) {
+        let value = "x".link(url);
+        assert_eq!(value.display_html(ColorSupport::True).to_string(), expected);
+    }
+}
@@ -0,0 +1,307 @@
+use super::{Escaper, Html, is_safe_url};
+use crate::Spec;
+use crate::ansi::{Class, Scanner, link_url, sgr, sgr_params};
+use std::fmt::Write as _;
+use std::io;
+
+/// Converts text that contains ANSI escape sequences to HTML.
+///
+/// This is a shortcut for writing the text to an [`HtmlWriter`].
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::ColorSupport;
+/// use chromaterm::html::{self, Html};
+///
+/// let text = "\x1B[1;31merror\x1B[0m: a < b";
+/// assert_eq!(
+///     html::from_ansi(text, Html::new(ColorSupport::True)),
+///     "<span style=\"font-weight:bold;color:#aa0000\">error</span>: a &lt; b",
+/// );
+/// ```
+pub fn from_ansi(text: &str, html: Html) -> String {
+    let mut writer = HtmlWriter::new(Vec::with_capacity(text.len()), html);
+    // NOTE Writing to a Vec never fails.
+    let _ = io::Write::write_all(&mut writer, text.as_bytes());
+    let bytes = writer.finish().unwrap_or_default();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Wraps a writer, and converts text that contains ANSI escape sequences to HTML while
+/// writing it.
+///
+/// SGR sequences (colors and styles) become `<span>` elements, and OSC 8 hyperlinks
+/// become `<a>` elements. Hyperlinks are only kept if their scheme is `http`, `https`,
+/// `mailto`, or `file`, so that untrusted input can't add scripts. All other escape sequences are removed, and text is escaped.
+/// Only a single escape sequence is buffered at a time, so arbitrarily large inputs
+/// can be converted, and escape sequences that are split across multiple writes are
+/// handled correctly.
+///
+/// Call [`finish`](Self::finish) after writing everything, to close any elements that
+/// are still open.
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::ColorSupport;
+/// use chromaterm::html::{Html, HtmlWriter};
+/// use std::io::Write;
+///
+/// let mut writer = HtmlWriter::new(Vec::new(), Html::new(ColorSupport::True).with_classes(true));
+/// writer.write_all(b"\x1B[3").unwrap();
+/// writer.write_all(b"2mok\x1B]8;;https://example.com\x1B\\docs").unwrap();
+/// let html = writer.finish().unwrap();
+/// assert_eq!(
+///     String::from_utf8(html).unwrap(),
+///     "<span class=\"ct-fg-green\">ok</span><a href=\"https://example.com\">\
+///      <span class=\"ct-fg-green\">docs</span></a>",
+/// );
+/// ```
+#[derive(Debug)]
+pub struct HtmlWriter<W: io::Write> {
+    inner: W,
+    html: Html,
+    scanner: Scanner,
+    /// The escape sequence that is being read.
+    sequence: Vec<u8>,
+    /// The active colors and styles.
+    spec: Spec,
+    /// The URL of the active hyperlink.
+    link: Option<String>,
+    /// If the elements for the active state need to be written before the next text.
+    is_stale: bool,
+    /// The start tags that are being written. It's kept to reuse its allocation.
+    open: String,
+    is_span_open: bool,
+    is_link_open: bool,
+}
+
+impl<W: io::Write> HtmlWriter<W> {
+    /// Escape sequences that are longer than this are ignored.
+    const MAX_SEQUENCE_LEN: usize = 4096;
+
+    /// Wraps the writer.
+    pub fn new(inner: W, html: Html) -> Self {
+        Self {
+            inner,
+            html,
+            scanner: Scanner::new(),
+            sequence: Vec::new(),
+            spec: Spec::new(),
+            link: None,
+            is_stale: false,
+            open: String::new(),
+            is_span_open: false,
+            is_link_open: false,
+        }
+    }
+
+    /// Gets a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the wrapped writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Closes any elements that are still open, flushes, and unwraps the writer. An
+    /// unfinished escape sequence is discarded.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.close(true)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    /// Closes the open `<span>`, and the open `<a>` if `link`.
+    fn close(&mut self, link: bool) -> io::Result<()> {
+        if self.is_span_open {
+            self.inner.write_all(b"</span>")?;
+            self.is_span_open = false;
+        }
+        if link && self.is_link_open {
+            self.inner.write_all(b"</a>")?;
+            self.is_link_open = false;
+        }
+        Ok(())
+    }
+
+    /// Updates the state with a finished escape sequence.
+    fn apply(&mut self) -> io::Result<()> {
+        let Ok(sequence) = std::str::from_utf8(&self.sequence) else {
+            return Ok(());
+        };
+        if let Some(params) = sgr_params(sequence) {
+            let spec = sgr::apply(self.spec, params, |_| {});
+            if spec != self.spec {
+                self.spec = spec;
+                self.close(false)?;
+                self.is_stale = true;
+            }
+        } else if let Some(url) = link_url(sequence) {
+            // NOTE Links with other schemes, like `javascript:`, are dropped.
+            let link = is_safe_url(url).then(|| url.to_owned());
+            if link != self.link {
+                self.link = link;
+                self.close(true)?;
+                self.is_stale = true;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes escaped text, opening elements for the active state first if needed.
+    fn write_text(&mut self, text: &[u8]) -> io::Result<()> {
+        if self.is_stale {
+            let mut open = std::mem::take(&mut self.open);
+            open.clear();
+            if let (Some(url), false) = (&self.link, self.is_link_open) {
+                open.push_str("<a href=\"");
+                // NOTE Writing to a String never fails.
+                let _ = Escaper(&mut open).write_str(url);
+                open.push_str("\">");
+                self.is_link_open = true;
+            }
+            if !self.is_span_open {
+                // NOTE Writing to a String never fails.
+                self.is_span_open = self
+                    .html
+                    .write_open(&self.spec, &self.spec, &mut open)
+                    .unwrap_or(false);
+            }
+            self.inner.write_all(open.as_bytes())?;
+            self.open = open;
+            self.is_stale = false;
+        }
+        let mut rest = text;
+        while let Some(index) = rest
+            .iter()
+            .position(|byte| matches!(byte, b'&' | b'<' | b'>' | b'"' | b'\''))
+        {
+            self.inner.write_all(&rest[..index])?;
+            let escaped: &[u8] = match rest[index] {
+                b'&' => b"&amp;",
+                b'<' => b"&lt;",
+                b'>' => b"&gt;",
+                b'"' => b"&quot;",
+                _ => b"&#39;",
+            };
+            self.inner.write_all(escaped)?;
+            rest = &rest[index + 1..];
+        }
+        self.inner.write_all(rest)
+    }
+}
+
+impl<W: io::Write> io::Write for HtmlWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut run_start = None;
+        for (index, &byte) in buf.iter().enumerate() {
+            let class = self.scanner.feed(byte);
+            if class != Class::Text {
+                if let Some(run) = run_start.take() {
+                    self.write_text(&buf[run..index])?;
+                }
+                if self.scanner.is_starting() {
+                    self.sequence.clear();
+                }
+                if self.sequence.len() < Self::MAX_SEQUENCE_LEN {
+                    self.sequence.push(byte);
+                }
+            }
+            match class {
+                Class::Text => {
+                    self.sequence.clear();
+                    run_start.get_or_insert(index);
+                }
+                Class::Escape => {}
+                Class::End => {
+                    if self.sequence.len() < Self::MAX_SEQUENCE_LEN {
+                        self.apply()?;
+                    }
+                    self.sequence.clear();
+                }
+            }
+        }
+        if let Some(run) = run_start {
+            self.write_text(&buf[run..])?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ColorSupport;
+    use crate::colors::{Palette, Simple, True};
+    use rstest::rstest;
+    use std::io::Write;
+
+    #[rstest]
+    #[case("plain & simple", "plain &amp; simple")]
+    #[case("\x1B[31mred\x1B[0m", "<span style=\"color:#aa0000\">red</span>")]
+    #[case(
+        "\x1B[1mbold \x1B[4mboth\x1B[22m underline\x1B[m",
+        "<span style=\"font-weight:bold\">bold </span>\
+         <span style=\"font-weight:bold;text-decoration:underline\">both</span>\
+         <span style=\"text-decoration:underline\"> underline</span>"
+    )]
+    #[case(
+        "\x1B[38;5;208;48;2;1;2;3mx",
+        "<span style=\"color:#ff8700;background-color:#010203\">x</span>"
+    )]
+    #[case("\x1B[31m\x1B[0m\x1B[2Kx", "x")]
+    #[case(
+        "\x1B]8;;https://example.com/?a&b\x07link\x1B]8;;\x07",
+        "<a href=\"https://example.com/?a&amp;b\">link</a>"
+    )]
+    #[case(
+        "\x1B]0;title\x07\x1B[3\x1B[1mx",
+        "<span style=\"font-weight:bold\">x</span>"
+    )]
+    #[case("\x1B]8;;javascript:alert(1)\x1B\\link\x1B]8;;\x1B\\", "link")]
+    #[case("\x1B]8;;JavaScript:alert(1)\x07link\x1B]8;;\x07", "link")]
+    #[case(
+        "\x1B]8;;mailto:a@example.com\x07mail\x1B]8;;\x07",
+        "<a href=\"mailto:a@example.com\">mail</a>"
+    )]
+    fn test_from_ansi(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(from_ansi(text, Html::new(ColorSupport::True)), expected);
+    }
+
+    #[test]
+    fn test_from_ansi_palette() {
+        let palette = Palette::default().with(Simple::Red, True::from_rgb(0xCD, 0, 0));
+        let html = Html::new(ColorSupport::True).with_palette(palette);
+        assert_eq!(
+            from_ansi("\x1B[31ma\x1B[38;5;1mb", html),
+            "<span style=\"color:#cd0000\">a</span><span style=\"color:#cd0000\">b</span>",
+        );
+    }
+
+    #[test]
+    fn test_writer_split_sequences() {
+        let input =
+            "\x1B[1;31merror\x1B[0m \x1B]8;;https://example.com\x1B\\caf\u{E9}\x1B]8;;\x1B\\";
+        let expected = from_ansi(input, Html::new(ColorSupport::True));
+        for size in 1..input.len() {
+            let mut writer = HtmlWriter::new(Vec::new(), Html::new(ColorSupport::True));
+            for chunk in input.as_bytes().chunks(size) {
+                writer.write_all(chunk).unwrap();
+            }
+            let html = writer.finish().unwrap();
+            assert_eq!(
+                String::from_utf8(html).unwrap(),
+                expected,
+                "chunk size {size}"
+            );
+        }
+    }
+}
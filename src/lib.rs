@@ -31,6 +31,79 @@
 //! chromaterm::config::use_color_support(support);
 //! ```
 //!
+//! ### Capabilities
+//!
+//! Support for some styles varies independently of color support. Styles that need a
+//! missing [`Capabilities`] are skipped, or substituted with a simpler style when
+//! converting to supported styles.
+//!
+//! ```rust
+//! use chromaterm::prelude::*;
+//! use chromaterm::{Capabilities, ColorSupport};
+//!
+//! chromaterm::config::use_capabilities(Capabilities::NONE);
+//! let italic = "italic".italic().display_fallback(ColorSupport::True);
+//! assert_eq!(italic.to_string(), "italic");
+//! let curly = "curly".curly_underline().display_fallback(ColorSupport::True);
+//! assert_eq!(curly.to_string(), "\x1B[4mcurly\x1B[0m");
+//! ```
+//!
+//! ### Color syntax
+//!
+//! 8-bit and true colors are written in the common semicolon form by default. Use
+//! [`config::use_color_syntax`] to write the ITU T.416 colon form instead, or
+//! [`config::use_default_color_syntax`] to choose it for terminals that are known to
+//! understand it.
+//!
+//! ```rust
+//! use chromaterm::prelude::*;
+//! use chromaterm::{ColorSupport, ColorSyntax};
+//!
+//! chromaterm::config::use_color_syntax(ColorSyntax::Colon);
+//! let orange = "orange".eight_bit(208).display_exact(ColorSupport::EightBit);
+//! assert_eq!(orange.to_string(), "\x1B[38:5:208morange\x1B[0m");
+//! ```
+//!
+//! ### Padding
+//!
+//! Colorized and styled values honor the formatter's width, fill, alignment, and
+//! precision. Escape sequences don't count towards the width, and wide characters,
+//! like CJK characters and emoji, count as 2 columns. The precision is passed
+//! through to the innermost value, so `{:.3}` truncates a string to 3 characters.
+//!
+//! By default, the padding is styled like the value, so background colors extend over
+//! it. Use [`config::style_padding`] to write the padding outside of the escape
+//! sequences instead.
+//!
+//! ```rust
+//! use chromaterm::prelude::*;
+//! use chromaterm::ColorSupport;
+//!
+//! chromaterm::config::use_color_support(ColorSupport::Simple);
+//! chromaterm::config::style_padding(true);
+//! assert_eq!(format!("{:>4}", "ok".on_red()), "\x1B[41m  ok\x1B[0m");
+//! chromaterm::config::style_padding(false);
+//! assert_eq!(format!("{:>4}", "ok".on_red()), "  \x1B[41mok\x1B[0m");
+//! ```
+//!
+//! ### Hyperlinks
+//!
+//! Terminal hyperlinks are configured separately from colors, and fall back to plain
+//! text when they are disabled.
+//!
+//! ```rust
+//! use chromaterm::prelude::*;
+//! use chromaterm::LinkFallback;
+//!
+//! chromaterm::config::use_hyperlinks(false);
+//! let link = "docs".link("https://docs.rs/chromaterm");
+//! assert_eq!(link.to_string(), "docs");
+//! let link = "docs"
+//!     .link("https://docs.rs/chromaterm")
+//!     .with_fallback(LinkFallback::TextWithUrl);
+//! assert_eq!(link.to_string(), "docs (https://docs.rs/chromaterm)");
+//! ```
+//!
 //! ### Configuration
 //!
 //! ```rust
@@ -60,29 +133,61 @@
 //! // Because no colors are supported, the string is plain.
 //! assert_eq!("not styled".rgb(255, 0, 0).to_string(), "not styled");
 //! ```
+pub use ansi::strip;
+pub use backend::DisplayWithBackend;
+pub use capabilities::Capabilities;
 pub use color::Color;
 pub use color_level::ColorLevel;
 pub use color_support::ColorSupport;
+pub use color_syntax::ColorSyntax;
 pub use colorize::Colorize;
 pub use colorizer::Colorizer;
 pub use colors::Colors;
-pub use display::{DisplayWithExact, DisplayWithFallback};
+pub use display::{DisplayWithExact, DisplayWithFallback, Plain};
+pub use html::DisplayWithHtml;
+pub use link::{Link, LinkFallback};
+pub use monochrome::{Layer, Monochrome};
+pub use paint::{Paint, Painted};
+pub use per_line::PerLine;
+pub use sanitize::{Sanitize, Sanitized};
+pub use spec::{Spec, Styled};
 pub use style::Style;
+pub use styled_writer::StyledWriter;
 pub use styler::Styler;
 pub use styles::Styles;
 pub use stylize::Stylize;
+pub use theme::{Role, Theme};
+pub use write_to::WriteTo;
 
+pub mod ansi;
+pub mod backend;
+mod capabilities;
 mod color;
 mod color_level;
 mod color_support;
+mod color_syntax;
 mod colorize;
 mod colorizer;
 pub mod colors;
 pub mod config;
 pub mod conversion;
 mod display;
+pub mod html;
+mod link;
+pub mod mirc;
+mod monochrome;
+mod paint;
+mod per_line;
 pub mod prelude;
+pub mod prompt;
+mod sanitize;
+mod spec;
 mod style;
+mod styled_writer;
 mod styler;
 pub mod styles;
 mod stylize;
+pub mod svg;
+pub mod theme;
+pub mod tmux;
+mod write_to;
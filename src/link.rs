@@ -0,0 +1,232 @@
+use crate::backend::{self, Attr, Backend, DisplayWithBackend};
+use crate::{ColorSupport, DisplayWithExact, DisplayWithFallback};
+use std::env;
+use std::fmt;
+
+/// What to write instead of a hyperlink when hyperlinks aren't supported.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub enum LinkFallback {
+    /// Only write the text.
+    #[default]
+    Text,
+    /// Write the text, followed by the URL in parentheses, like `text (url)`.
+    TextWithUrl,
+}
+
+/// Wraps a value in an [OSC 8] terminal hyperlink.
+///
+/// Whether hyperlinks are written is controlled by the
+/// [`Capabilities::HYPERLINKS`](crate::Capabilities::HYPERLINKS) capability (see
+/// [`config::use_hyperlinks`](crate::config::use_hyperlinks)), and is *not* affected by
+/// the color support level. Control characters in the URL are percent-encoded, so
+/// that the URL can't add escape sequences of its own.
+///
+/// [OSC 8]: https://gist.github.com/egmontkob/eb114294efbcd5adb1944c9f3cb5feda
+pub struct Link<U, D> {
+    url: U,
+    value: D,
+    fallback: LinkFallback,
+}
+
+impl<U: AsRef<str>, D> Link<U, D> {
+    /// Wraps the displayable value with a link to the URL.
+    pub fn new(value: D, url: U) -> Self {
+        Self {
+            url,
+            value,
+            fallback: LinkFallback::default(),
+        }
+    }
+
+    /// Sets what should be written when hyperlinks aren't supported.
+    pub fn with_fallback(mut self, fallback: LinkFallback) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Displays the wrapped value as a hyperlink if `enabled`, and the fallback
+    /// otherwise.
+    fn fmt_impl<F: FnOnce(&mut fmt::Formatter<'_>) -> Result<(), fmt::Error>>(
+        &self,
+        enabled: bool,
+        f: &mut fmt::Formatter<'_>,
+        wrapped: F,
+    ) -> Result<(), fmt::Error> {
+        let url = self.url.as_ref();
+        if enabled {
+            write!(f, "\x1B]8;;{}\x1B\\", EncodedUrl(url))?;
+            wrapped(f)?;
+            return write!(f, "\x1B]8;;\x1B\\");
+        }
+        wrapped(f)?;
+        match self.fallback {
+            LinkFallback::Text => Ok(()),
+            LinkFallback::TextWithUrl => write!(f, " ({})", EncodedUrl(url)),
+        }
+    }
+}
+
+impl<U: AsRef<str>, D: DisplayWithExact> DisplayWithExact for Link<U, D> {
+    /// Displays the contained value as a hyperlink if hyperlinks are enabled.
+    #[inline]
+    fn fmt_exact(
+        &self,
+        supported: ColorSupport,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        let enabled = crate::config::get_hyperlinks();
+        self.fmt_impl(enabled, f, |f| self.value.fmt_exact(supported, f))
+    }
+
+    /// Aligns like the contained value.
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        self.value.default_alignment()
+    }
+}
+
+impl<U: AsRef<str>, D: DisplayWithFallback> DisplayWithFallback for Link<U, D> {
+    /// Displays the contained value as a hyperlink if hyperlinks are enabled.
+    #[inline]
+    fn fmt_fallback(
+        &self,
+        supported: ColorSupport,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        let enabled = crate::config::get_hyperlinks();
+        self.fmt_impl(enabled, f, |f| self.value.fmt_fallback(supported, f))
+    }
+
+    /// Aligns like the contained value.
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        self.value.default_alignment()
+    }
+}
+
+impl<U: AsRef<str>, D: DisplayWithBackend> DisplayWithBackend for Link<U, D> {
+    /// Sends the hyperlink to the backend. The backend decides if hyperlinks are
+    /// shown, so the fallback is never used.
+    fn fmt_backend(
+        &self,
+        backend: &mut dyn Backend,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        let attr = Attr::Link(self.url.as_ref());
+        backend::fmt_attrs(backend, &[attr], f, |backend, f| {
+            self.value.fmt_backend(backend, f)
+        })
+    }
+}
+
+impl<U: AsRef<str>, D: DisplayWithExact + DisplayWithFallback> fmt::Display for Link<U, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        crate::display::fmt_configured(self, f)
+    }
+}
+
+/// Displays a URL with its control characters percent-encoded, so that it can't end
+/// a hyperlink's escape sequence early, or add escape sequences of its own.
+pub(crate) struct EncodedUrl<'a>(pub(crate) &'a str);
+
+impl fmt::Display for EncodedUrl<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let mut rest = self.0;
+        while let Some(index) = rest.find(char::is_control) {
+            f.write_str(&rest[..index])?;
+            let mut c = rest[index..].chars();
+            let mut buf = [0; 4];
+            if let Some(control) = c.next() {
+                for byte in control.encode_utf8(&mut buf).bytes() {
+                    write!(f, "%{byte:02X}")?;
+                }
+            }
+            rest = c.as_str();
+        }
+        f.write_str(rest)
+    }
+}
+
+/// Detects if the terminal supports hyperlinks from environment variables.
+pub(crate) fn hyperlinks_from_env() -> bool {
+    hyperlinks_from_vars(|key| env::var(key).ok())
+}
+
+/// Detects hyperlink support from environment variables, using `var` to look them up.
+pub(crate) fn hyperlinks_from_vars<V: Fn(&str) -> Option<String>>(var: V) -> bool {
+    if let Some(value) = var("FORCE_HYPERLINK") {
+        return !matches!(value.as_str(), "" | "0" | "false");
+    }
+    if var("WT_SESSION").is_some() || var("KONSOLE_VERSION").is_some() {
+        return true;
+    }
+    let vte_version = var("VTE_VERSION").and_then(|version| version.parse::<u32>().ok());
+    if vte_version.is_some_and(|version| version >= 5000) {
+        return true;
+    }
+    let term_program = var("TERM_PROGRAM").unwrap_or_default();
+    if ["iTerm.app", "WezTerm", "vscode", "Hyper", "ghostty"].contains(&term_program.as_str()) {
+        return true;
+    }
+    let term = var("TERM").unwrap_or_default();
+    ["kitty", "foot", "alacritty", "wezterm", "ghostty"]
+        .into_iter()
+        .any(|hint| term.contains(hint))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    /// Helper to display a link with hyperlinks explicitly enabled or disabled.
+    struct Forced<'a>(&'a Link<&'a str, &'a str>, bool);
+
+    impl fmt::Display for Forced<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+            self.0
+                .fmt_impl(self.1, f, |f| self.0.value.fmt_exact(ColorSupport::None, f))
+        }
+    }
+
+    #[rstest]
+    #[case(
+        true,
+        LinkFallback::Text,
+        "\x1B]8;;https://example.com\x1B\\text\x1B]8;;\x1B\\"
+    )]
+    #[case(false, LinkFallback::Text, "text")]
+    #[case(false, LinkFallback::TextWithUrl, "text (https://example.com)")]
+    fn test_fmt_impl(
+        #[case] enabled: bool,
+        #[case] fallback: LinkFallback,
+        #[case] expected: &str,
+    ) {
+        let link = Link::new("text", "https://example.com").with_fallback(fallback);
+        assert_eq!(Forced(&link, enabled).to_string(), expected);
+    }
+
+    #[rstest]
+    #[case(&[], false)]
+    #[case(&[("FORCE_HYPERLINK", "1")], true)]
+    #[case(&[("FORCE_HYPERLINK", "0"), ("WT_SESSION", "1")], false)]
+    #[case(&[("WT_SESSION", "1")], true)]
+    #[case(&[("VTE_VERSION", "4800")], false)]
+    #[case(&[("VTE_VERSION", "6003")], true)]
+    #[case(&[("TERM_PROGRAM", "WezTerm")], true)]
+    #[case(&[("TERM", "xterm-kitty")], true)]
+    #[case(&[("TERM", "xterm-256color")], false)]
+    fn test_hyperlinks_from_vars(#[case] vars: &[(&str, &str)], #[case] expected: bool) {
+        let var = crate::color_support::lookup_vars(vars);
+        assert_eq!(hyperlinks_from_vars(var), expected);
+    }
+
+    #[rstest]
+    #[case(true, "\x1B]8;;http://a%1B[2Jb%07%C2%9B\x1B\\x\x1B]8;;\x1B\\")]
+    #[case(false, "x (http://a%1B[2Jb%07%C2%9B)")]
+    fn test_url_injection(#[case] enabled: bool, #[case] expected: &str) {
+        let link =
+            Link::new("x", "http://a\x1B[2Jb\x07\u{9B}").with_fallback(LinkFallback::TextWithUrl);
+        assert_eq!(Forced(&link, enabled).to_string(), expected);
+    }
+}
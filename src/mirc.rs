@@ -0,0 +1,194 @@
+//! Rendering colorized and styled values with mIRC formatting codes.
+//!
+//! IRC clients, and many chat bridges, show colors and styles with control codes
+//! instead of escape sequences. Colors are written as `\x03` followed by the text
+//! color and an optional background color, like `\x0304,01`, from a palette of 16
+//! colors. [`Mirc`] is a [`Backend`] that writes these codes.
+//!
+//! # Example
+//!
+//! ```rust
+//! use chromaterm::prelude::*;
+//! use chromaterm::mirc::Mirc;
+//!
+//! let value = "error".bright_red().bold();
+//! assert_eq!(
+//!     value.display_with(Mirc::new()).to_string(),
+//!     "\x02\x0304\x02\x02error\x0F\x0F",
+//! );
+//! ```
+use crate::backend::{Attr, Backend};
+use crate::colors::Simple;
+use crate::{Color, Colors, Spec, Styles};
+use std::fmt;
+
+/// The RGB values of the mIRC colors, in the order of their codes.
+const PALETTE: [(u8, u8, u8); 16] = [
+    (0xFF, 0xFF, 0xFF),
+    (0x00, 0x00, 0x00),
+    (0x00, 0x00, 0x7F),
+    (0x00, 0x93, 0x00),
+    (0xFF, 0x00, 0x00),
+    (0x7F, 0x00, 0x00),
+    (0x9C, 0x00, 0x9C),
+    (0xFC, 0x7F, 0x00),
+    (0xFF, 0xFF, 0x00),
+    (0x00, 0xFC, 0x00),
+    (0x00, 0x93, 0x93),
+    (0x00, 0xFF, 0xFF),
+    (0x00, 0x00, 0xFC),
+    (0xFF, 0x00, 0xFF),
+    (0x7F, 0x7F, 0x7F),
+    (0xD2, 0xD2, 0xD2),
+];
+
+/// The code that keeps the client's default color. This is used for the text color
+/// when only a background color is set.
+const DEFAULT_CODE: u8 = 99;
+
+/// A [`Backend`] that writes mIRC formatting codes.
+///
+/// Simple colors are written as their matching mIRC color, and other colors are
+/// written as the closest color in the mIRC palette. Bold, italic, underline,
+/// strikethrough, and reverse are written as their formatting codes, curly underlines
+/// are written as underlines, and other styles are ignored. Each value is closed with
+/// `\x0F`, which resets all colors and styles. Hyperlinks are ignored, because
+/// clients find URLs in the text themselves.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Mirc {
+    /// For each open group, if anything was written.
+    open: Vec<bool>,
+}
+
+impl Mirc {
+    /// Creates a backend.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { open: Vec::new() }
+    }
+}
+
+/// Gets the formatting code for a style, if mIRC has one.
+const fn style_code(style: Styles) -> Option<char> {
+    match style {
+        Styles::Bold => Some('\x02'),
+        Styles::Italic => Some('\x1D'),
+        Styles::Underline | Styles::CurlyUnderline => Some('\x1F'),
+        Styles::Strike => Some('\x1E'),
+        Styles::Reverse => Some('\x16'),
+        Styles::Dim | Styles::Blink | Styles::Hidden => None,
+    }
+}
+
+/// Gets the mIRC color code for a color.
+fn color_code(color: Colors) -> u8 {
+    let Colors::Simple(simple) = color else {
+        return closest(color.rgb_u8());
+    };
+    match simple {
+        Simple::Black => 1,
+        Simple::Red => 5,
+        Simple::Green => 3,
+        Simple::Yellow => 7,
+        Simple::Blue => 2,
+        Simple::Magenta => 6,
+        Simple::Cyan => 10,
+        Simple::White => 15,
+        Simple::BrightBlack => 14,
+        Simple::BrightRed => 4,
+        Simple::BrightGreen => 9,
+        Simple::BrightYellow => 8,
+        Simple::BrightBlue => 12,
+        Simple::BrightMagenta => 13,
+        Simple::BrightCyan => 11,
+        Simple::BrightWhite => 0,
+    }
+}
+
+/// Gets the code of the palette color that is closest to the RGB values.
+fn closest((r, g, b): (u8, u8, u8)) -> u8 {
+    let distance = |&(_, &(pr, pg, pb)): &(usize, &(u8, u8, u8))| {
+        let dr = i32::from(r) - i32::from(pr);
+        let dg = i32::from(g) - i32::from(pg);
+        let db = i32::from(b) - i32::from(pb);
+        dr * dr + dg * dg + db * db
+    };
+    let (code, _) = PALETTE
+        .iter()
+        .enumerate()
+        .min_by_key(distance)
+        .unwrap_or((0, &PALETTE[0]));
+    // NOTE The palette has 16 colors, so the code always fits.
+    code as u8
+}
+
+impl Backend for Mirc {
+    fn open(&mut self, attrs: &[Attr<'_>], f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let spec = Spec::from_attrs(attrs);
+        let mut is_open = false;
+        for code in spec.styles().iter().filter_map(style_code) {
+            write!(f, "{code}")?;
+            is_open = true;
+        }
+        let fg = spec.fg().map(color_code);
+        let bg = spec.bg().map(color_code);
+        // NOTE Codes are always written with two digits, so that text starting with a
+        //      digit isn't read as part of the code. A text color without a background
+        //      is followed by two bold codes, which cancel out, so that text starting
+        //      with a comma and a digit isn't read as a background. The background
+        //      isn't written instead, because that would reset an outer background.
+        match (fg, bg) {
+            (Some(fg), None) => write!(f, "\x03{fg:02}\x02\x02")?,
+            (fg, Some(bg)) => write!(f, "\x03{:02},{bg:02}", fg.unwrap_or(DEFAULT_CODE))?,
+            (None, None) => {}
+        }
+        is_open |= fg.is_some() || bg.is_some();
+        self.open.push(is_open);
+        Ok(())
+    }
+
+    fn close(&mut self, _attrs: &[Attr<'_>], f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if self.open.pop().unwrap_or_default() {
+            f.write_str("\x0F")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::{EightBit, True};
+    use crate::{Colorize, DisplayWithBackend, Stylize};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Colors::Simple(Simple::Red), 5)]
+    #[case(Colors::Simple(Simple::BrightWhite), 0)]
+    #[case(Colors::EightBit(EightBit::from(208)), 7)]
+    #[case(Colors::EightBit(EightBit::from(21)), 12)]
+    #[case(Colors::True(True::from_rgb(0x80, 0x80, 0x80)), 14)]
+    #[case(Colors::True(True::from_rgb(0xFF, 0x10, 0xF0)), 13)]
+    fn test_color_code(#[case] color: Colors, #[case] expected: u8) {
+        assert_eq!(color_code(color), expected);
+    }
+
+    #[rstest]
+    #[case("1".green(), "\x0303\x02\x021\x0F")]
+    #[case(",5 items".green(), "\x0303\x02\x02,5 items\x0F")]
+    #[case("x".on_blue(), "\x0399,02x\x0F")]
+    #[case("x".dim(), "x")]
+    #[case("x".curly_underline(), "\x1Fx\x0F")]
+    fn test_mirc<D: DisplayWithBackend>(#[case] value: D, #[case] expected: &str) {
+        assert_eq!(value.display_with(Mirc::new()).to_string(), expected);
+    }
+
+    #[test]
+    fn test_spec() {
+        let value = "x".styled().strike().rgb(0, 0, 0).on_rgb(255, 255, 255);
+        assert_eq!(
+            value.display_with(Mirc::new()).to_string(),
+            "\x1E\x0301,00x\x0F",
+        );
+    }
+}
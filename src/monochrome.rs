@@ -0,0 +1,142 @@
+use crate::colors::Simple;
+use crate::conversion::ToSimple;
+use crate::{Color, ColorSupport, Colors, Styles};
+
+/// Whether a color is used for the text or the background.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Layer {
+    /// The text color.
+    Fg,
+    /// The background color.
+    Bg,
+}
+
+/// How colors should be shown when there is no color support at all.
+///
+/// Without a strategy, colors are simply dropped, so text that was red and text that
+/// was green look identical. Some terminals (like `TERM=vt100`) can't show colors, but
+/// *can* show styles like bold, underline, and reverse. A strategy maps colors to
+/// those styles, so that differently-colored text can still be told apart.
+///
+/// Monochrome strategies are only used when converting to supported colors. See
+/// [`config::use_monochrome`](crate::config::use_monochrome).
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::colors::Simple;
+/// use chromaterm::prelude::*;
+/// use chromaterm::{ColorSupport, Layer, Monochrome, Styles};
+///
+/// fn semantic(color: Simple, layer: Layer) -> Option<Styles> {
+///     match (color, layer) {
+///         (Simple::Red | Simple::BrightRed, Layer::Fg) => Some(Styles::Bold),
+///         (Simple::Yellow | Simple::BrightYellow, Layer::Fg) => Some(Styles::Underline),
+///         (_, Layer::Bg) => Some(Styles::Reverse),
+///         _ => None,
+///     }
+/// }
+///
+/// chromaterm::config::use_monochrome(Monochrome::Map(semantic));
+/// let error = "error".red().display_fallback(ColorSupport::None);
+/// assert_eq!(error.to_string(), "\x1B[1merror\x1B[0m");
+/// let success = "success".green().display_fallback(ColorSupport::None);
+/// assert_eq!(success.to_string(), "success");
+/// ```
+#[derive(Debug, Default, Copy, Clone)]
+pub enum Monochrome {
+    /// Colors are dropped.
+    #[default]
+    Off,
+    /// Light and saturated text colors become bold, dark grays become dim, and light
+    /// background colors become reversed.
+    Luminance,
+    /// Colors are converted to the closest simple color, and then mapped to a style.
+    Map(fn(Simple, Layer) -> Option<Styles>),
+}
+
+impl Monochrome {
+    /// Luminance at or above this is "light."
+    const LIGHT: u32 = 160;
+    /// Luminance below this is "dark."
+    const DARK: u32 = 64;
+    /// Saturation at or above this is "saturated."
+    const SATURATED: u8 = 128;
+
+    /// Checks if colors are mapped to styles.
+    #[inline]
+    pub fn is_on(&self) -> bool {
+        !matches!(self, Self::Off)
+    }
+
+    /// Gets the style that should be shown instead of the color.
+    pub fn to_style(&self, color: &Colors, layer: Layer) -> Option<Styles> {
+        match self {
+            Self::Off => None,
+            Self::Luminance => {
+                let rgb = color.rgb_u8();
+                let luminance = luminance(rgb);
+                // NOTE Saturated colors, like red for errors, are usually used to stand
+                //      out, even if they are dark.
+                match layer {
+                    Layer::Fg if luminance >= Self::LIGHT => Some(Styles::Bold),
+                    Layer::Fg if saturation(rgb) >= Self::SATURATED => Some(Styles::Bold),
+                    Layer::Fg if luminance < Self::DARK => Some(Styles::Dim),
+                    Layer::Bg if luminance >= Self::LIGHT => Some(Styles::Reverse),
+                    Layer::Fg | Layer::Bg => None,
+                }
+            }
+            Self::Map(map) => map(color.to_simple(), layer),
+        }
+    }
+}
+
+/// Gets the monochrome strategy for falling back to the color support. Colors are only
+/// shown as styles when there is no color support at all.
+#[inline]
+pub(crate) fn monochrome_for(supported: ColorSupport) -> Monochrome {
+    if supported == ColorSupport::None {
+        crate::config::get_monochrome()
+    } else {
+        Monochrome::Off
+    }
+}
+
+/// Approximates the relative luminance of a color in the range `[0, 256)`.
+#[inline]
+fn luminance((r, g, b): (u8, u8, u8)) -> u32 {
+    // NOTE Rec. 709 coefficients, scaled to integers that add up to 10,000.
+    (2126 * r as u32 + 7152 * g as u32 + 722 * b as u32) / 10_000
+}
+
+/// Approximates the saturation of a color as its chroma, in the range `[0, 256)`.
+#[inline]
+fn saturation((r, g, b): (u8, u8, u8)) -> u8 {
+    r.max(g).max(b) - r.min(g).min(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Colors::new_bright_white(), Layer::Fg, Some(Styles::Bold))]
+    #[case(Colors::new_bright_green(), Layer::Fg, Some(Styles::Bold))]
+    #[case(Colors::new_green(), Layer::Fg, Some(Styles::Bold))]
+    #[case(Colors::new_red(), Layer::Fg, Some(Styles::Bold))]
+    #[case(Colors::new_blue(), Layer::Fg, Some(Styles::Bold))]
+    #[case(Colors::new_true_color(0x80, 0x40, 0x40), Layer::Fg, None)]
+    #[case(Colors::new_bright_black(), Layer::Fg, None)]
+    #[case(Colors::new_black(), Layer::Fg, Some(Styles::Dim))]
+    #[case(Colors::new_true_color(0x10, 0x10, 0x10), Layer::Fg, Some(Styles::Dim))]
+    #[case(Colors::new_white(), Layer::Bg, Some(Styles::Reverse))]
+    #[case(Colors::new_black(), Layer::Bg, None)]
+    fn test_luminance_to_style(
+        #[case] color: Colors,
+        #[case] layer: Layer,
+        #[case] expected: Option<Styles>,
+    ) {
+        assert_eq!(Monochrome::Luminance.to_style(&color, layer), expected);
+    }
+}
@@ -0,0 +1,107 @@
+use crate::display::FmtFn;
+use crate::{ColorSupport, Spec};
+use std::fmt;
+
+/// A [`Spec`] that is compiled for a color support, so that it can be applied many
+/// times without deriving its escape sequences again.
+///
+/// Compiling converts unsupported colors to supported colors, and applies the
+/// configured [`Monochrome`](crate::Monochrome) strategy and
+/// [`Capabilities`](crate::Capabilities) *once*. Changing the configuration afterwards
+/// doesn't change a compiled paint. Applying it is then only writing the prefix, the
+/// value, and the suffix, which is useful in hot loops, like rendering table cells.
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::{ColorSupport, Paint, Spec};
+///
+/// let red = Paint::compile(Spec::new().rgb(255, 0, 0).bold(), ColorSupport::Simple);
+/// assert_eq!(red.prefix(), "\x1B[1;91m");
+/// assert_eq!(red.suffix(), "\x1B[0m");
+/// assert_eq!(red.paint(42).to_string(), "\x1B[1;91m42\x1B[0m");
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Paint {
+    prefix: String,
+    suffix: &'static str,
+}
+
+impl Paint {
+    /// Compiles the spec for the color support. If nothing is supported, the prefix
+    /// and suffix are empty.
+    pub fn compile(spec: Spec, supported: ColorSupport) -> Self {
+        let prefix =
+            FmtFn(|f: &mut fmt::Formatter<'_>| spec.fmt_open(supported, true, f).map(|_| ()))
+                .to_string();
+        let suffix = if prefix.is_empty() { "" } else { "\x1B[0m" };
+        Self { prefix, suffix }
+    }
+
+    /// Gets the escape sequence that is written before a value.
+    #[inline]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Gets the escape sequence that is written after a value.
+    #[inline]
+    pub fn suffix(&self) -> &'static str {
+        self.suffix
+    }
+
+    /// Wraps a displayable value, so that it's displayed with the prefix and suffix.
+    #[inline]
+    pub fn paint<D: fmt::Display>(&self, value: D) -> Painted<'_, D> {
+        Painted { paint: self, value }
+    }
+}
+
+/// A value that is displayed with a compiled [`Paint`].
+///
+/// Create this with [`Paint::paint`].
+#[derive(Debug, Clone, Copy)]
+pub struct Painted<'a, D> {
+    paint: &'a Paint,
+    value: D,
+}
+
+impl<D: fmt::Display> fmt::Display for Painted<'_, D> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(&self.paint.prefix)?;
+        self.value.fmt(f)?;
+        f.write_str(self.paint.suffix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DisplayWithFallback, Styled};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Spec::new(), ColorSupport::True)]
+    #[case(Spec::new().red(), ColorSupport::None)]
+    #[case(Spec::new().red().on_blue(), ColorSupport::Simple)]
+    #[case(Spec::new().eight_bit(208).italic(), ColorSupport::Simple)]
+    #[case(Spec::new().rgb(1, 2, 3).on_eight_bit(42), ColorSupport::EightBit)]
+    #[case(Spec::new().rgb(1, 2, 3).underline(), ColorSupport::True)]
+    fn test_matches_styled(#[case] spec: Spec, #[case] supported: ColorSupport) {
+        let paint = Paint::compile(spec, supported);
+        assert_eq!(
+            paint.paint("text").to_string(),
+            Styled::new("text", spec)
+                .display_fallback(supported)
+                .to_string(),
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let paint = Paint::compile(Spec::new().green(), ColorSupport::None);
+        assert_eq!(paint, Paint::default());
+        assert_eq!(paint.paint("text").to_string(), "text");
+    }
+}
@@ -0,0 +1,391 @@
+use crate::ansi::{Class, Scanner, WidthCounter, sgr, sgr_params};
+use crate::backend::{Backend, DisplayWithBackend};
+use crate::display::{Flags, FmtFn, Padding};
+use crate::{ColorSupport, DisplayWithExact, DisplayWithFallback, Spec};
+use std::fmt::{self, Write};
+
+/// Wraps a colorized or styled value, and reapplies its colors and styles on every
+/// line.
+///
+/// Normally, a multi-line value is written with a single escape sequence before the
+/// first line and a single reset after the last line. Pagers like `less -R`, and
+/// other tools that process text line by line, then lose the colors on later lines.
+/// This resets the colors and styles before every line break, and reopens them after
+/// it.
+///
+/// Create this with `per_line` on the *outermost* wrapper, so that every color and
+/// style is reapplied. Only colors and styles are reapplied, not hyperlinks.
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::prelude::*;
+/// use chromaterm::ColorSupport;
+///
+/// let value = "one\ntwo".red().bold().per_line();
+/// assert_eq!(
+///     value.display_exact(ColorSupport::Simple).to_string(),
+///     "\x1B[1m\x1B[31mone\x1B[0m\n\x1B[1;31mtwo\x1B[0m",
+/// );
+/// ```
+pub struct PerLine<D> {
+    value: D,
+}
+
+impl<D> PerLine<D> {
+    /// Wraps the displayable value.
+    pub fn new(value: D) -> Self {
+        Self { value }
+    }
+
+    /// Writes the formatted value through a `LineWriter`. The formatter's width, fill,
+    /// and alignment are applied by the `LineWriter`, so that the padding is styled
+    /// like the text next to it, and the precision and the `+` and `#` flags are passed
+    /// through to the value. The value is aligned with `alignment` if the formatter
+    /// doesn't have an alignment.
+    fn fmt_impl<F: Fn(&mut fmt::Formatter<'_>) -> Result<(), fmt::Error>>(
+        f: &mut fmt::Formatter<'_>,
+        alignment: fmt::Alignment,
+        wrapped: F,
+    ) -> Result<(), fmt::Error> {
+        let wrapped = FmtFn(wrapped);
+        let flags = Flags::of(f);
+        let (padding, text_left) = match f.width() {
+            Some(width) => {
+                let mut measure = Measure::default();
+                flags.write(&wrapped, &mut measure)?;
+                let padding = width.saturating_sub(measure.counter.width());
+                let alignment = f.align().unwrap_or(alignment);
+                (Padding::new(padding, f.fill(), alignment), measure.text_len)
+            }
+            None => (Padding::default(), 0),
+        };
+        let mut writer = LineWriter::new(f, padding, text_left);
+        flags.write(&wrapped, &mut writer)?;
+        writer.finish()
+    }
+}
+
+impl<D: DisplayWithExact> DisplayWithExact for PerLine<D> {
+    /// Displays the contained value, reapplying the colors that are supported on every
+    /// line.
+    #[inline]
+    fn fmt_exact(
+        &self,
+        supported: ColorSupport,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        let alignment = self.value.default_alignment();
+        Self::fmt_impl(f, alignment, |f| self.value.fmt_exact(supported, f))
+    }
+
+    /// Aligns like the contained value.
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        self.value.default_alignment()
+    }
+}
+
+impl<D: DisplayWithFallback> DisplayWithFallback for PerLine<D> {
+    /// Displays the contained value, reapplying the supported colors that it falls back
+    /// to on every line.
+    #[inline]
+    fn fmt_fallback(
+        &self,
+        supported: ColorSupport,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        let alignment = self.value.default_alignment();
+        Self::fmt_impl(f, alignment, |f| self.value.fmt_fallback(supported, f))
+    }
+
+    /// Aligns like the contained value.
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        self.value.default_alignment()
+    }
+}
+
+impl<D: DisplayWithBackend> DisplayWithBackend for PerLine<D> {
+    /// Sends the contained value to the backend as is. Backends get the attributes of
+    /// the whole value, so they can handle line breaks themselves.
+    #[inline]
+    fn fmt_backend(
+        &self,
+        backend: &mut dyn Backend,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        self.value.fmt_backend(backend, f)
+    }
+}
+
+impl<D: DisplayWithExact + DisplayWithFallback> fmt::Display for PerLine<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        crate::display::fmt_configured(self, f)
+    }
+}
+
+/// SGR sequences that are longer than this are passed through without being read by
+/// `LineWriter`. Sequences written by this crate are much shorter.
+const MAX_SEQUENCE_LEN: usize = 64;
+
+/// Measures the width and the number of bytes of text of a value.
+#[derive(Debug, Default)]
+struct Measure {
+    counter: WidthCounter,
+    scanner: Scanner,
+    text_len: usize,
+}
+
+impl Write for Measure {
+    fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
+        self.counter.count(s);
+        for byte in s.bytes() {
+            if self.scanner.feed(byte) == Class::Text {
+                self.text_len += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Passes everything through to a formatter, keeping track of the active colors and
+/// styles, and resetting them before line breaks. They are reopened before the next
+/// text, so empty lines don't get any escape sequences. Resets from the value are
+/// skipped if nothing is active.
+struct LineWriter<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    scanner: Scanner,
+    /// The control sequence that is being held back, until it's known if it needs to
+    /// be written.
+    sequence: [u8; MAX_SEQUENCE_LEN],
+    /// The length of the control sequence that is being held back.
+    len: usize,
+    /// If a control sequence is being held back.
+    is_held: bool,
+    /// The active colors and styles.
+    spec: Spec,
+    /// If the colors and styles were reset for a line break, and need to be reopened
+    /// before the next text.
+    is_pending: bool,
+    /// The fill characters that are written before the first byte of text, and after
+    /// the last byte of text. Like padding that is styled, they're written right next
+    /// to the text, inside of all escape sequences.
+    padding: Padding,
+    /// The number of bytes of text that haven't been written yet.
+    text_left: usize,
+}
+
+impl<'a, 'b> LineWriter<'a, 'b> {
+    /// Wraps the formatter.
+    fn new(f: &'a mut fmt::Formatter<'b>, padding: Padding, text_left: usize) -> Self {
+        Self {
+            f,
+            scanner: Scanner::new(),
+            sequence: [0; MAX_SEQUENCE_LEN],
+            len: 0,
+            is_held: false,
+            spec: Spec::new(),
+            is_pending: false,
+            padding,
+            text_left,
+        }
+    }
+
+    /// Writes the control sequence that is being held back, and stops holding it.
+    fn release(&mut self) -> Result<(), fmt::Error> {
+        self.is_held = false;
+        self.f.write_str(held(&self.sequence[..self.len])?)
+    }
+
+    /// Handles a control sequence that has been held back until its end. SGR sequences
+    /// update the active colors and styles, and resets are skipped if nothing is
+    /// active, or if the colors and styles were already reset for a line break.
+    fn end(&mut self) -> Result<(), fmt::Error> {
+        let Some(params) = sgr_params(held(&self.sequence[..self.len])?) else {
+            return self.release();
+        };
+        let is_reset = params.split(';').all(|param| matches!(param, "" | "0"));
+        let is_redundant = is_reset && (self.is_pending || self.spec.is_empty());
+        self.spec = sgr::apply(self.spec, params, |_| {});
+        if is_redundant {
+            self.is_held = false;
+            return Ok(());
+        }
+        self.release()
+    }
+
+    /// Writes fill characters. They're text, so the colors and styles are reopened
+    /// first if they were reset for a line break.
+    fn fill(&mut self, count: usize) -> Result<(), fmt::Error> {
+        if count > 0 {
+            self.reopen()?;
+        }
+        (0..count).try_for_each(|_| self.f.write_char(self.padding.fill))
+    }
+
+    /// Reopens the colors and styles if they were reset for a line break.
+    fn reopen(&mut self) -> Result<(), fmt::Error> {
+        if self.is_pending {
+            self.spec.fmt_raw(self.f)?;
+            self.is_pending = false;
+        }
+        Ok(())
+    }
+
+    /// Writes a control sequence that never ended, and the padding if there was no text
+    /// to write it next to.
+    fn finish(mut self) -> Result<(), fmt::Error> {
+        if self.is_held {
+            self.release()?;
+        }
+        let count = self.padding.before + self.padding.after;
+        self.padding.before = 0;
+        self.padding.after = 0;
+        self.fill(count)
+    }
+}
+
+/// Gets a control sequence that was held back. It only holds ASCII bytes.
+#[inline]
+fn held(sequence: &[u8]) -> Result<&str, fmt::Error> {
+    std::str::from_utf8(sequence).map_err(|_| fmt::Error)
+}
+
+impl Write for LineWriter<'_, '_> {
+    fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
+        let mut run_start = 0;
+        for (index, byte) in s.bytes().enumerate() {
+            let class = self.scanner.feed(byte);
+            if class != Class::Text {
+                if self.scanner.is_starting() {
+                    if self.is_held {
+                        self.release()?;
+                    }
+                    self.f.write_str(&s[run_start..index])?;
+                    run_start = index;
+                    self.len = 0;
+                    self.is_held = true;
+                }
+                if !self.is_held {
+                    continue;
+                }
+                // NOTE Only control sequences can be SGR sequences. Other sequences,
+                //      and sequences that are too long, are passed through.
+                let is_control = self.len != 1 || byte == b'[';
+                match self.sequence.get_mut(self.len) {
+                    Some(slot) if is_control && byte.is_ascii() => {
+                        *slot = byte;
+                        self.len += 1;
+                        run_start = index + 1;
+                    }
+                    _ => self.release()?,
+                }
+                if self.is_held && class == Class::End {
+                    self.end()?;
+                }
+                continue;
+            }
+            if self.is_held {
+                // NOTE The control sequence was malformed, so it's written as it is.
+                self.release()?;
+            }
+            if self.padding.before > 0 {
+                self.f.write_str(&s[run_start..index])?;
+                run_start = index;
+                let count = std::mem::take(&mut self.padding.before);
+                self.fill(count)?;
+            }
+            match (byte == b'\n', self.is_pending) {
+                (true, false) if !self.spec.is_empty() => {
+                    self.f.write_str(&s[run_start..index])?;
+                    self.f.write_str("\x1B[0m")?;
+                    run_start = index;
+                    self.is_pending = true;
+                }
+                (false, true) => {
+                    self.f.write_str(&s[run_start..index])?;
+                    run_start = index;
+                    self.reopen()?;
+                }
+                _ => {}
+            }
+            self.text_left = self.text_left.saturating_sub(1);
+            if self.text_left == 0 && self.padding.after > 0 {
+                // NOTE The last byte of text always ends a character.
+                self.f.write_str(&s[run_start..=index])?;
+                run_start = index + 1;
+                let count = std::mem::take(&mut self.padding.after);
+                self.fill(count)?;
+            }
+        }
+        self.f.write_str(&s[run_start..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Colorize, Stylize};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("single", ColorSupport::True, "\x1B[31msingle\x1B[0m")]
+    #[case("a\nb", ColorSupport::True, "\x1B[31ma\x1B[0m\n\x1B[31mb\x1B[0m")]
+    #[case(
+        "a\n\nb\n",
+        ColorSupport::True,
+        "\x1B[31ma\x1B[0m\n\n\x1B[31mb\x1B[0m\n"
+    )]
+    #[case("a\nb", ColorSupport::None, "a\nb")]
+    fn test_red(#[case] text: &str, #[case] supported: ColorSupport, #[case] expected: &str) {
+        let value = text.red().per_line();
+        assert_eq!(value.display_exact(supported).to_string(), expected);
+    }
+
+    #[test]
+    fn test_nested() {
+        let value = "a\nb".rgb(1, 2, 3).on_blue().italic().per_line();
+        assert_eq!(
+            value.display_fallback(ColorSupport::True).to_string(),
+            "\x1B[3m\x1B[44m\x1B[38;2;1;2;3ma\x1B[0m\n\x1B[3;38;2;1;2;3;44mb\x1B[0m",
+        );
+    }
+
+    #[test]
+    fn test_styled() {
+        let value = "a\nb".styled().bold().green().per_line();
+        assert_eq!(
+            value.display_exact(ColorSupport::Simple).to_string(),
+            "\x1B[1;32ma\x1B[0m\n\x1B[1;32mb\x1B[0m",
+        );
+    }
+
+    /// Displays red text per line with simple colors, passing the formatter through.
+    fn red_per_line(text: &str) -> impl fmt::Display + '_ {
+        let value = text.red().per_line();
+        FmtFn(move |f: &mut fmt::Formatter<'_>| value.fmt_exact(ColorSupport::Simple, f))
+    }
+
+    /// Displays a red number per line with simple colors, passing the formatter through.
+    fn red_number_per_line(number: i32) -> impl fmt::Display {
+        let value = number.red().per_line();
+        FmtFn(move |f: &mut fmt::Formatter<'_>| value.fmt_exact(ColorSupport::Simple, f))
+    }
+
+    #[rstest]
+    #[case(format!("[{:>6}]", red_per_line("ok")), "[\x1B[31m    ok\x1B[0m]")]
+    #[case(format!("[{:^7}]", red_per_line("ok")), "[\x1B[31m  ok   \x1B[0m]")]
+    #[case(format!("[{:.1}]", red_per_line("ok")), "[\x1B[31mo\x1B[0m]")]
+    #[case(
+        format!("[{:-<4}]", red_per_line("a\nb")),
+        "[\x1B[31ma\x1B[0m\n\x1B[31mb-\x1B[0m]"
+    )]
+    #[case(format!("[{:->4}]", red_per_line("a\n")), "[\x1B[31m--a\x1B[0m\n]")]
+    #[case(format!("[{:-<3}]", red_per_line("")), "[\x1B[31m\x1B[0m---]")]
+    #[case(format!("[{:5}]", red_number_per_line(42)), "[\x1B[31m   42\x1B[0m]")]
+    #[case(format!("[{:<+5}]", red_number_per_line(42)), "[\x1B[31m+42  \x1B[0m]")]
+    fn test_padded(#[case] formatted: String, #[case] expected: &str) {
+        assert_eq!(formatted, expected);
+    }
+}
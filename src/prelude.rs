@@ -1,3 +1,6 @@
 //! Re-exports the traits required to "magically" add the colorization and styling
 //! methods to strings.
-pub use crate::{Colorize, DisplayWithExact, DisplayWithFallback, Stylize};
+pub use crate::{
+    Colorize, DisplayWithBackend, DisplayWithExact, DisplayWithFallback, DisplayWithHtml, Stylize,
+    WriteTo,
+};
@@ -0,0 +1,251 @@
+//! Rendering colorized and styled values for shell prompts.
+//!
+//! Shells measure the prompt to place the cursor, so escape sequences in a prompt
+//! must be marked as taking no space, or line editing breaks. [`Prompt`] is a
+//! [`Backend`] that marks them the way each [`Shell`] expects.
+//!
+//! # Example
+//!
+//! ```rust
+//! use chromaterm::prelude::*;
+//! use chromaterm::prompt::{Prompt, Shell};
+//! use chromaterm::ColorSupport;
+//!
+//! let branch = "main".green().bold();
+//! let bash = Prompt::new(Shell::Bash, ColorSupport::Simple);
+//! assert_eq!(
+//!     (&branch).display_with(bash).to_string(),
+//!     "\\[\x1B[1m\\]\\[\x1B[32m\\]main\\[\x1B[0m\\]\\[\x1B[0m\\]",
+//! );
+//!
+//! let zsh = Prompt::new(Shell::Zsh, ColorSupport::Simple);
+//! assert_eq!(branch.display_with(zsh).to_string(), "%B%F{2}main%f%b");
+//! ```
+use crate::backend::{Attr, Backend};
+use crate::display::FmtFn;
+use crate::link::EncodedUrl;
+use crate::{Color, ColorSupport, Colors, Spec, Styles};
+use std::fmt::{self, Write};
+
+/// A shell that the prompt is written for.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Shell {
+    /// Escape sequences are wrapped in `\[` and `\]`. Backslashes, dollar signs, and
+    /// backticks are escaped, so that they aren't expanded by the `promptvars` option.
+    Bash,
+    /// Colors, bold, and underline are written with zsh's own syntax, like `%F{208}`
+    /// and `%B`. Other escape sequences are wrapped in `%{` and `%}`, and percent
+    /// signs are escaped.
+    Zsh,
+    /// Escape sequences are written as is, because fish measures the prompt itself.
+    Fish,
+}
+
+impl Shell {
+    /// Writes text, escaping the characters that the shell would expand.
+    fn write_escaped<W: Write>(self, text: &str, w: &mut W) -> Result<(), fmt::Error> {
+        // NOTE bash first expands its own backslash escapes, and then, with the
+        //      `promptvars` option that is on by default, expands the prompt like text
+        //      in double quotes. The characters that are special in double quotes get
+        //      an escaped backslash, which is left for the second expansion.
+        let escape: fn(char) -> Option<&'static str> = match self {
+            Self::Bash => |c| match c {
+                '\\' => Some("\\\\\\\\"),
+                '$' => Some("\\\\$"),
+                '`' => Some("\\\\`"),
+                _ => None,
+            },
+            Self::Zsh => |c| (c == '%').then_some("%%"),
+            Self::Fish => return w.write_str(text),
+        };
+        let mut start = 0;
+        for (index, c) in text.char_indices() {
+            if let Some(escaped) = escape(c) {
+                w.write_str(&text[start..index])?;
+                w.write_str(escaped)?;
+                start = index + c.len_utf8();
+            }
+        }
+        w.write_str(&text[start..])
+    }
+
+    /// Writes escape sequences, marking them as taking no space.
+    fn write_invisible<W: Write>(self, sequence: &str, w: &mut W) -> Result<(), fmt::Error> {
+        if sequence.is_empty() {
+            return Ok(());
+        }
+        let (start, end) = match self {
+            Self::Bash => ("\\[", "\\]"),
+            Self::Zsh => ("%{", "%}"),
+            Self::Fish => ("", ""),
+        };
+        w.write_str(start)?;
+        self.write_escaped(sequence, w)?;
+        w.write_str(end)
+    }
+}
+
+/// A [`Backend`] that writes escape sequences for a shell prompt, like `PS1` in bash,
+/// `PROMPT` in zsh, or the output of `fish_prompt`.
+///
+/// Like [`Ansi`](crate::backend::Ansi), unsupported colors are converted to supported
+/// colors, and hyperlinks are written if they are enabled with
+/// [`config::use_hyperlinks`](crate::config::use_hyperlinks). Text is escaped, so that
+/// the shell shows it as it is, even if it has something like `$(...)` in it, which
+/// bash would otherwise run with its `promptvars` option. zsh's `PROMPT_SUBST` option
+/// is off by default, so text isn't escaped for it.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Prompt {
+    shell: Shell,
+    support: ColorSupport,
+    /// For each open group, what closes it.
+    close: Vec<String>,
+}
+
+impl Prompt {
+    /// Creates a backend for the shell and color support.
+    #[inline]
+    pub const fn new(shell: Shell, support: ColorSupport) -> Self {
+        Self {
+            shell,
+            support,
+            close: Vec::new(),
+        }
+    }
+
+    /// Writes what opens and closes the colors and styles of the spec.
+    fn write_spec(&self, spec: Spec, open: &mut String, close: &mut String) -> fmt::Result {
+        let spec = spec.resolve(self.support, true);
+        let spec = if self.shell == Shell::Zsh {
+            write_zsh(spec, open, close)?
+        } else {
+            spec
+        };
+        let sequence = FmtFn(|f: &mut fmt::Formatter<'_>| spec.fmt_raw(f).map(|_| ())).to_string();
+        self.shell.write_invisible(&sequence, open)?;
+        if !sequence.is_empty() {
+            // NOTE Styles written with zsh's own syntax are closed after the reset,
+            //      so that zsh knows that they are closed.
+            let mut reset = String::new();
+            self.shell.write_invisible("\x1B[0m", &mut reset)?;
+            close.insert_str(0, &reset);
+        }
+        Ok(())
+    }
+}
+
+/// Writes the colors and styles that zsh has its own syntax for, and returns the
+/// rest of the spec.
+fn write_zsh(spec: Spec, open: &mut String, close: &mut String) -> Result<Spec, fmt::Error> {
+    let mut rest = spec;
+    for (style, start, end) in [(Styles::Bold, "%B", "%b"), (Styles::Underline, "%U", "%u")] {
+        if spec.styles().contains(style) {
+            open.push_str(start);
+            close.insert_str(0, end);
+            rest = rest.remove_style(style);
+        }
+    }
+    if let Some(color) = spec.fg() {
+        write!(open, "%F{{{}}}", FmtFn(|f| fmt_zsh_color(color, f)))?;
+        close.insert_str(0, "%f");
+        rest = rest.clear_color();
+    }
+    if let Some(color) = spec.bg() {
+        write!(open, "%K{{{}}}", FmtFn(|f| fmt_zsh_color(color, f)))?;
+        close.insert_str(0, "%k");
+        rest = rest.clear_on_color();
+    }
+    Ok(rest)
+}
+
+/// Writes a color the way zsh's `%F` and `%K` expect it.
+fn fmt_zsh_color(color: Colors, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+    match color {
+        Colors::Simple(simple) => write!(f, "{}", simple as u8),
+        Colors::EightBit(eight_bit) => write!(f, "{}", eight_bit.lookup()),
+        Colors::True(true_color) => {
+            let (r, g, b) = true_color.rgb_u8();
+            write!(f, "#{r:02x}{g:02x}{b:02x}")
+        }
+    }
+}
+
+impl Backend for Prompt {
+    fn open(&mut self, attrs: &[Attr<'_>], f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let link = attrs.iter().find_map(|attr| match attr {
+            Attr::Link(url) => Some(*url),
+            _ => None,
+        });
+        let link = link.filter(|_| crate::config::get_hyperlinks());
+        let mut open = String::new();
+        let mut close = String::new();
+        if let Some(url) = link {
+            self.shell
+                .write_invisible(&format!("\x1B]8;;{}\x1B\\", EncodedUrl(url)), &mut open)?;
+            self.shell.write_invisible("\x1B]8;;\x1B\\", &mut close)?;
+        }
+        self.write_spec(Spec::from_attrs(attrs), &mut open, &mut close)?;
+        self.close.push(close);
+        f.write_str(&open)
+    }
+
+    fn close(&mut self, _attrs: &[Attr<'_>], f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let close = self.close.pop().unwrap_or_default();
+        f.write_str(&close)
+    }
+
+    fn text(&mut self, text: &str, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        self.shell.write_escaped(text, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Colorize, DisplayWithBackend, Stylize};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Shell::Bash, "\\[\x1B[31m\\]a\\\\\\\\b\\[\x1B[0m\\]")]
+    #[case(Shell::Zsh, "%F{1}a\\b%f")]
+    #[case(Shell::Fish, "\x1B[31ma\\b\x1B[0m")]
+    fn test_red(#[case] shell: Shell, #[case] expected: &str) {
+        let value = "a\\b".red();
+        let prompt = Prompt::new(shell, ColorSupport::Simple);
+        assert_eq!(value.display_with(prompt).to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("$(whoami)", "\\[\x1B[31m\\]\\\\$(whoami)\\[\x1B[0m\\]")]
+    #[case("`id`", "\\[\x1B[31m\\]\\\\`id\\\\`\\[\x1B[0m\\]")]
+    #[case("\\$HOME", "\\[\x1B[31m\\]\\\\\\\\\\\\$HOME\\[\x1B[0m\\]")]
+    fn test_bash_expansions(#[case] text: &str, #[case] expected: &str) {
+        let prompt = Prompt::new(Shell::Bash, ColorSupport::Simple);
+        assert_eq!(text.red().display_with(prompt).to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("100%".bright_red(), ColorSupport::Simple, "%F{9}100%%%f")]
+    #[case("x".eight_bit(208), ColorSupport::EightBit, "%F{208}x%f")]
+    #[case("x".eight_bit(208), ColorSupport::Simple, "%F{11}x%f")]
+    #[case("x".on_rgb(255, 0, 10), ColorSupport::True, "%K{#ff000a}x%k")]
+    #[case("x".red(), ColorSupport::None, "x")]
+    fn test_zsh_colors<D: DisplayWithBackend>(
+        #[case] value: D,
+        #[case] supported: ColorSupport,
+        #[case] expected: &str,
+    ) {
+        let prompt = Prompt::new(Shell::Zsh, supported);
+        assert_eq!(value.display_with(prompt).to_string(), expected);
+    }
+
+    #[test]
+    fn test_zsh_styles() {
+        let value = "x".styled().blue().bold().underline().italic();
+        let prompt = Prompt::new(Shell::Zsh, ColorSupport::Simple);
+        assert_eq!(
+            value.display_with(prompt).to_string(),
+            "%B%U%F{4}%{\x1B[3m%}x%{\x1B[0m%}%f%u%b",
+        );
+    }
+}
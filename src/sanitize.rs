@@ -0,0 +1,246 @@
+use crate::ansi::{Class, Scanner};
+use crate::display::{FmtFn, fmt_plain};
+use crate::{ColorSupport, DisplayWithExact, DisplayWithFallback};
+use std::fmt::{self, Write};
+
+/// How [`Sanitized`] writes control characters and escape sequences.
+///
+/// Line breaks and tabs are always kept.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub enum Sanitize {
+    /// Escape sequences and other control characters are removed. This includes
+    /// sequences that start with a C1 control character, like `CSI` (`U+009B`).
+    #[default]
+    Strip,
+    /// Control characters are written in caret notation, like `^[` for `ESC`, so
+    /// escape sequences are shown as text, like `^[[2J`.
+    Caret,
+    /// Control characters are written as Unicode control pictures, like `␛` for
+    /// `ESC`, so escape sequences are shown as text, like `␛[2J`.
+    ControlPictures,
+}
+
+impl Sanitize {
+    /// Writes the replacement for a control character.
+    fn write_replacement<W: Write>(self, c: char, w: &mut W) -> Result<(), fmt::Error> {
+        let code = u32::from(c);
+        match (self, code) {
+            (Self::Strip, _) => Ok(()),
+            (Self::Caret, 0x7F) => w.write_str("^?"),
+            (Self::Caret, 0x00..=0x1F) => write!(w, "^{}", to_char(code + 0x40)),
+            // NOTE C1 control characters are the same as `ESC` followed by a 7-bit
+            //      character, so they're written like that.
+            (Self::Caret, _) => write!(w, "^[{}", to_char(code - 0x40)),
+            (Self::ControlPictures, 0x7F) => w.write_char('\u{2421}'),
+            (Self::ControlPictures, 0x00..=0x1F) => w.write_char(to_char(0x2400 + code)),
+            (Self::ControlPictures, _) => write!(w, "\u{241B}{}", to_char(code - 0x40)),
+        }
+    }
+}
+
+/// Converts a code point that is known to be valid.
+#[inline]
+fn to_char(code: u32) -> char {
+    char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+/// Checks if a character must be sanitized.
+#[inline]
+fn is_unsafe(c: char) -> bool {
+    c.is_control() && c != '\n' && c != '\t'
+}
+
+/// Wraps an untrusted [`Display`](fmt::Display) value, and removes or escapes its
+/// control characters and escape sequences before writing it.
+///
+/// Text from users, files, or other programs can contain escape sequences that change
+/// the terminal's title, clear the screen, or worse. Wrap it before coloring or
+/// styling it, so that only the colors and styles from this crate reach the terminal.
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::prelude::*;
+/// use chromaterm::{ColorSupport, Sanitize, Sanitized};
+///
+/// let input = "user\x1B]0;pwned\x07\x1B[2J";
+/// let value = Sanitized::new(input, Sanitize::Strip).red();
+/// assert_eq!(
+///     value.display_exact(ColorSupport::Simple).to_string(),
+///     "\x1B[31muser\x1B[0m",
+/// );
+/// let value = Sanitized::new(input, Sanitize::Caret);
+/// assert_eq!(value.to_string(), "user^[]0;pwned^G^[[2J");
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Sanitized<T> {
+    value: T,
+    policy: Sanitize,
+}
+
+impl<T> Sanitized<T> {
+    /// Wraps the value, sanitizing it with the policy.
+    #[inline]
+    pub fn new(value: T, policy: Sanitize) -> Self {
+        Self { value, policy }
+    }
+}
+
+impl<T: fmt::Display> Sanitized<T> {
+    /// Writes the sanitized value.
+    pub(crate) fn fmt_sanitized<W: Write>(&self, w: W) -> Result<(), fmt::Error> {
+        let mut writer = SanitizeWriter {
+            inner: w,
+            policy: self.policy,
+            scanner: Scanner::new(),
+        };
+        write!(writer, "{}", self.value)
+    }
+}
+
+impl<T: fmt::Display> DisplayWithExact for Sanitized<T> {
+    /// Formats the sanitized value, never checking for color support, because the
+    /// value itself is never colored.
+    fn fmt_exact(
+        &self,
+        _supported: ColorSupport,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl<T: fmt::Display> DisplayWithFallback for Sanitized<T> {
+    /// Formats the sanitized value, never checking for color support, because the
+    /// value itself is never colored.
+    fn fmt_fallback(
+        &self,
+        _supported: ColorSupport,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Sanitized<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        fmt_plain(
+            &FmtFn(|f: &mut fmt::Formatter<'_>| self.fmt_sanitized(f)),
+            f,
+        )
+    }
+}
+
+/// Passes text through, removing or escaping control characters and escape sequences.
+struct SanitizeWriter<W: Write> {
+    inner: W,
+    policy: Sanitize,
+    /// Finds escape sequences to remove, if they are stripped.
+    scanner: Scanner,
+}
+
+impl<W: Write> SanitizeWriter<W> {
+    /// Checks if the character is text, and not part of an escape sequence. Escape
+    /// sequences are only found if they are stripped, because they're otherwise shown
+    /// as text.
+    fn is_text(&mut self, c: char) -> bool {
+        if self.policy != Sanitize::Strip {
+            return true;
+        }
+        // NOTE C1 control characters are the same as `ESC` followed by a 7-bit
+        //      character, so that `CSI`, `OSC`, and `DCS` start sequences, and `ST`
+        //      ends them.
+        if let code @ 0x80..=0x9F = u32::from(c) {
+            self.scanner.feed(0x1B);
+            return self.scanner.feed((code - 0x40) as u8) == Class::Text;
+        }
+        // NOTE Every byte is fed to the scanner, even after one isn't text, so that
+        //      the scanner's state stays correct.
+        let mut buf = [0; 4];
+        let mut is_text = true;
+        for byte in c.encode_utf8(&mut buf).bytes() {
+            is_text &= self.scanner.feed(byte) == Class::Text;
+        }
+        is_text
+    }
+}
+
+impl<W: Write> Write for SanitizeWriter<W> {
+    fn write_str(&mut self, s: &str) -> Result<(), fmt::Error> {
+        let mut run_start = 0;
+        for (index, c) in s.char_indices() {
+            let is_text = self.is_text(c);
+            if is_text && !is_unsafe(c) {
+                continue;
+            }
+            self.inner.write_str(&s[run_start..index])?;
+            run_start = index + c.len_utf8();
+            if is_text {
+                self.policy.write_replacement(c, &mut self.inner)?;
+            }
+        }
+        self.inner.write_str(&s[run_start..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Colorize;
+    use rstest::rstest;
+
+    const INPUT: &str = "a\x1B]0;title\x07b\x1B[2Jc\rd\u{9B}e\x7F\n\tf";
+
+    #[rstest]
+    #[case(Sanitize::Strip, "abcd\n\tf")]
+    #[case(Sanitize::Caret, "a^[]0;title^Gb^[[2Jc^Md^[[e^?\n\tf")]
+    #[case(
+        Sanitize::ControlPictures,
+        "a\u{241B}]0;title\u{2407}b\u{241B}[2Jc\u{240D}d\u{241B}[e\u{2421}\n\tf"
+    )]
+    fn test_policies(#[case] policy: Sanitize, #[case] expected: &str) {
+        assert_eq!(Sanitized::new(INPUT, policy).to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("a\u{9B}2Jb\x1B[2Jc", "abc")]
+    #[case("a\u{9D}0;title\u{9C}b", "ab")]
+    #[case("a\u{9D}0;title\x07b", "ab")]
+    #[case("a\u{90}1$r0m\x1B\\b", "ab")]
+    #[case("a\u{85}b", "ab")]
+    fn test_strip_c1(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(Sanitized::new(input, Sanitize::Strip).to_string(), expected);
+    }
+
+    /// Writes an escape sequence that is split across writes.
+    struct Split;
+
+    impl fmt::Display for Split {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+            f.write_str("x\x1B[")?;
+            f.write_str("31my")
+        }
+    }
+
+    #[test]
+    fn test_split_sequence() {
+        assert_eq!(Sanitized::new(Split, Sanitize::Strip).to_string(), "xy");
+    }
+
+    #[test]
+    fn test_colorized() {
+        let value = Sanitized::new("\x1B[1mx", Sanitize::Strip).on_blue();
+        assert_eq!(
+            value.display_fallback(ColorSupport::Simple).to_string(),
+            "\x1B[44mx\x1B[0m",
+        );
+        assert_eq!(
+            format!("{:>3}", Sanitized::new("\x07x", Sanitize::Caret)),
+            "^Gx"
+        );
+        assert_eq!(
+            format!("{:>3}", Sanitized::new("\x07x", Sanitize::Strip)),
+            "  x"
+        );
+    }
+}
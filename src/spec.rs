@@ -0,0 +1,509 @@
+use crate::backend::{self, Attr, Backend, DisplayWithBackend};
+use crate::colors::{EightBit, True, simple};
+use crate::styles::{self, StyleSet};
+use crate::{
+    ColorSupport, ColorSyntax, Colors, DisplayWithExact, DisplayWithFallback, Layer, Monochrome,
+    Style, Styles,
+};
+use std::fmt;
+use std::ops::Deref;
+
+/// A flattened description of colors and styles.
+///
+/// Unlike chaining [`Colorize`](crate::Colorize) methods, which *wraps* values, setting
+/// a foreground or background color on a `Spec` replaces the previous one. Styles are
+/// collected into a set, so setting a style twice has no extra effect.
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::Spec;
+///
+/// let spec = Spec::new().red().bold().green();
+/// assert_eq!(spec, Spec::new().green().bold());
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct Spec {
+    fg: Option<Colors>,
+    bg: Option<Colors>,
+    styles: StyleSet,
+}
+
+/// Implements the named coloring and styling methods in terms of `color`, `on_color`,
+/// and `style`.
+macro_rules! impl_spec_methods {
+    () => {
+        impl_spec_methods!(@colors
+            (black, on_black, Black),
+            (red, on_red, Red),
+            (green, on_green, Green),
+            (blue, on_blue, Blue),
+            (yellow, on_yellow, Yellow),
+            (cyan, on_cyan, Cyan),
+            (magenta, on_magenta, Magenta),
+            (white, on_white, White),
+            (bright_black, on_bright_black, BrightBlack),
+            (bright_red, on_bright_red, BrightRed),
+            (bright_green, on_bright_green, BrightGreen),
+            (bright_blue, on_bright_blue, BrightBlue),
+            (bright_yellow, on_bright_yellow, BrightYellow),
+            (bright_cyan, on_bright_cyan, BrightCyan),
+            (bright_magenta, on_bright_magenta, BrightMagenta),
+            (bright_white, on_bright_white, BrightWhite)
+        );
+
+        impl_spec_methods!(@styles
+            (bold, Bold),
+            (dim, Dim),
+            (italic, Italic),
+            (underline, Underline),
+            (strike, Strike),
+            (reverse, Reverse),
+            (blink, Blink),
+            (curly_underline, CurlyUnderline),
+            (hidden, Hidden)
+        );
+
+        /// Sets the text to a color according to the 8-bit lookup table, replacing
+        /// any previous text color.
+        pub fn eight_bit(self, lookup: u8) -> Self {
+            self.color(EightBit::from(lookup))
+        }
+
+        /// Sets the background to a color according to the 8-bit lookup table,
+        /// replacing any previous background color.
+        pub fn on_eight_bit(self, lookup: u8) -> Self {
+            self.on_color(EightBit::from(lookup))
+        }
+
+        /// Sets text to a true color, replacing any previous text color.
+        pub fn rgb(self, r: u8, g: u8, b: u8) -> Self {
+            self.color(True::from_rgb(r, g, b))
+        }
+
+        /// Sets background to a true color, replacing any previous background color.
+        pub fn on_rgb(self, r: u8, g: u8, b: u8) -> Self {
+            self.on_color(True::from_rgb(r, g, b))
+        }
+    };
+    (@colors $(($fg:ident, $bg:ident, $color:ident)),+) => {
+        $(
+            #[doc = concat!("Sets the text color to `", stringify!($color), "`, replacing any previous text color.")]
+            pub fn $fg(self) -> Self {
+                self.color(simple::$color)
+            }
+
+            #[doc = concat!("Sets the background color to `", stringify!($color), "`, replacing any previous background color.")]
+            pub fn $bg(self) -> Self {
+                self.on_color(simple::$color)
+            }
+        )+
+    };
+    (@styles $(($fn_name:ident, $style:ident)),+) => {
+        $(
+            #[doc = concat!("Adds the `", stringify!($style), "` style.")]
+            pub fn $fn_name(self) -> Self {
+                self.style(styles::$style)
+            }
+        )+
+    };
+}
+
+impl Spec {
+    /// Creates a spec without any colors or styles.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            fg: None,
+            bg: None,
+            styles: StyleSet::new(),
+        }
+    }
+
+    /// Sets the text color, replacing any previous text color.
+    pub fn color<C: Into<Colors>>(mut self, color: C) -> Self {
+        self.fg = Some(color.into());
+        self
+    }
+
+    /// Sets the background color, replacing any previous background color.
+    pub fn on_color<C: Into<Colors>>(mut self, color: C) -> Self {
+        self.bg = Some(color.into());
+        self
+    }
+
+    /// Adds a style.
+    pub fn style<S: Into<Styles>>(mut self, style: S) -> Self {
+        self.styles.insert(style);
+        self
+    }
+
+    impl_spec_methods!();
+
+    /// Removes the text color.
+    pub fn clear_color(mut self) -> Self {
+        self.fg = None;
+        self
+    }
+
+    /// Removes the background color.
+    pub fn clear_on_color(mut self) -> Self {
+        self.bg = None;
+        self
+    }
+
+    /// Removes a style.
+    pub fn remove_style<S: Into<Styles>>(mut self, style: S) -> Self {
+        self.styles.remove(style);
+        self
+    }
+
+    /// Gets the text color.
+    #[inline]
+    pub fn fg(&self) -> Option<Colors> {
+        self.fg
+    }
+
+    /// Gets the background color.
+    #[inline]
+    pub fn bg(&self) -> Option<Colors> {
+        self.bg
+    }
+
+    /// Gets the styles.
+    #[inline]
+    pub fn styles(&self) -> StyleSet {
+        self.styles
+    }
+
+    /// Checks if the spec has no colors and no styles.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.fg.is_none() && self.bg.is_none() && self.styles.is_empty()
+    }
+
+    /// Gets the attributes of the spec, in the order they are sent to a backend.
+    pub(crate) fn attrs(&self) -> Attrs {
+        let styles = self.styles.iter().map(Attr::Style);
+        let fg = self.fg.map(Attr::Fg);
+        let bg = self.bg.map(Attr::Bg);
+        let mut attrs = Attrs {
+            attrs: [Attr::Style(Styles::Bold); Attrs::MAX_LEN],
+            len: 0,
+        };
+        for (slot, attr) in attrs.attrs.iter_mut().zip(styles.chain(fg).chain(bg)) {
+            *slot = attr;
+            attrs.len += 1;
+        }
+        attrs
+    }
+
+    /// Creates a spec from attributes, ignoring hyperlinks.
+    #[inline]
+    pub(crate) fn from_attrs(attrs: &[Attr<'_>]) -> Self {
+        Self::new().with_attrs(attrs)
+    }
+
+    /// Adds the attributes to the spec, ignoring hyperlinks.
+    pub(crate) fn with_attrs(self, attrs: &[Attr<'_>]) -> Self {
+        attrs.iter().fold(self, |spec, attr| match *attr {
+            Attr::Fg(color) => spec.color(color),
+            Attr::Bg(color) => spec.on_color(color),
+            Attr::Style(style) => spec.style(style),
+            Attr::Link(_) => spec,
+        })
+    }
+
+    /// Gets the colors and styles that are shown with the color support. If
+    /// `fallback` is `true`, unsupported colors are converted to supported colors, or
+    /// to styles if there is no color support and a [`Monochrome`] strategy is used.
+    pub(crate) fn resolve(&self, supported: ColorSupport, fallback: bool) -> Self {
+        let convert = |color: Colors| {
+            if fallback {
+                color.fallback(supported)
+            } else {
+                color.exact(supported)
+            }
+        };
+        let mut styles = self.styles;
+        let monochrome = if fallback {
+            crate::monochrome::monochrome_for(supported)
+        } else {
+            Monochrome::Off
+        };
+        if monochrome.is_on() {
+            let fg = self.fg.and_then(|c| monochrome.to_style(&c, Layer::Fg));
+            let bg = self.bg.and_then(|c| monochrome.to_style(&c, Layer::Bg));
+            fg.into_iter()
+                .chain(bg)
+                .for_each(|style| styles.insert(style));
+        }
+        let styles = if supported > ColorSupport::None || monochrome.is_on() {
+            let capabilities = crate::config::get_capabilities();
+            styles
+                .iter()
+                .filter_map(|style| style.resolve(capabilities, fallback))
+                .collect()
+        } else {
+            StyleSet::new()
+        };
+        Self {
+            fg: self.fg.and_then(convert),
+            bg: self.bg.and_then(convert),
+            styles,
+        }
+    }
+
+    /// Writes a single escape sequence for all supported colors and styles, like
+    /// [`resolve`](Self::resolve).
+    ///
+    /// Returns `true` if anything was written, meaning that the caller should reset
+    /// the style afterwards.
+    pub(crate) fn fmt_open(
+        &self,
+        supported: ColorSupport,
+        fallback: bool,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<bool, fmt::Error> {
+        self.resolve(supported, fallback).fmt_raw(f)
+    }
+
+    /// Writes a single escape sequence for all colors and styles, without checking
+    /// support or capabilities.
+    ///
+    /// Returns `true` if anything was written.
+    pub(crate) fn fmt_raw(&self, f: &mut fmt::Formatter<'_>) -> Result<bool, fmt::Error> {
+        self.fmt_raw_with_syntax(crate::config::get_color_syntax(), f)
+    }
+
+    /// Like [`fmt_raw`](Self::fmt_raw), but writes colors with the color syntax instead
+    /// of the configured one.
+    pub(crate) fn fmt_raw_with_syntax(
+        &self,
+        syntax: ColorSyntax,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<bool, fmt::Error> {
+        let mut params = Params::default();
+        for style in self.styles.iter() {
+            params.next(f)?;
+            style.fmt_style(f)?;
+        }
+        if let Some(color) = self.fg {
+            params.next(f)?;
+            color.fmt_with_syntax(Layer::Fg, syntax, f)?;
+        }
+        if let Some(color) = self.bg {
+            params.next(f)?;
+            color.fmt_with_syntax(Layer::Bg, syntax, f)?;
+        }
+        params.finish(f)
+    }
+}
+
+/// Helper for writing `;`-separated parameters in a single escape sequence.
+#[derive(Default)]
+struct Params {
+    open: bool,
+}
+
+impl Params {
+    /// Prepares to write the next parameter.
+    #[inline]
+    fn next(&mut self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let prefix = if self.open { ";" } else { "\x1B[" };
+        self.open = true;
+        f.write_str(prefix)
+    }
+
+    /// Ends the escape sequence, if one was started. Returns if one was started.
+    #[inline]
+    fn finish(self, f: &mut fmt::Formatter<'_>) -> Result<bool, fmt::Error> {
+        if self.open {
+            f.write_str("m")?;
+        }
+        Ok(self.open)
+    }
+}
+
+/// Wraps a value with a [`Spec`]. Later colors replace earlier ones instead of
+/// wrapping them, and the value is written with a single escape sequence.
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::prelude::*;
+/// use chromaterm::ColorSupport;
+///
+/// let styled = "green".styled().red().green().bold();
+/// assert_eq!(
+///     styled.display_exact(ColorSupport::True).to_string(),
+///     "\x1B[1;32mgreen\x1B[0m",
+/// );
+/// ```
+pub struct Styled<D> {
+    spec: Spec,
+    value: D,
+}
+
+impl<D> Styled<D> {
+    /// Wraps the displayable value with a spec.
+    pub fn new(value: D, spec: Spec) -> Self {
+        Self { spec, value }
+    }
+
+    /// Reapplies the colors and styles on every line. See [`PerLine`](crate::PerLine).
+    pub fn per_line(self) -> crate::PerLine<Self> {
+        crate::PerLine::new(self)
+    }
+
+    /// Sets the text color, replacing any previous text color.
+    pub fn color<C: Into<Colors>>(self, color: C) -> Self {
+        self.map_spec(|spec| spec.color(color))
+    }
+
+    /// Sets the background color, replacing any previous background color.
+    pub fn on_color<C: Into<Colors>>(self, color: C) -> Self {
+        self.map_spec(|spec| spec.on_color(color))
+    }
+
+    /// Adds a style.
+    pub fn style<S: Into<Styles>>(self, style: S) -> Self {
+        self.map_spec(|spec| spec.style(style))
+    }
+
+    impl_spec_methods!();
+
+    /// Gets the spec.
+    #[inline]
+    pub fn spec(&self) -> &Spec {
+        &self.spec
+    }
+
+    #[inline]
+    fn map_spec<F: FnOnce(Spec) -> Spec>(mut self, f: F) -> Self {
+        self.spec = f(self.spec);
+        self
+    }
+}
+
+impl<D: DisplayWithExact> DisplayWithExact for Styled<D> {
+    /// Displays the contained value, including only the colors that are supported.
+    fn fmt_exact(
+        &self,
+        supported: ColorSupport,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        let is_open = self.spec.fmt_open(supported, false, f)?;
+        self.value.fmt_exact(supported, f)?;
+        if is_open {
+            write!(f, "\x1B[0m")?;
+        }
+        Ok(())
+    }
+
+    /// Aligns like the contained value.
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        self.value.default_alignment()
+    }
+}
+
+impl<D: DisplayWithFallback> DisplayWithFallback for Styled<D> {
+    /// Displays the contained value, and tries to fall back to colors that are
+    /// supported.
+    fn fmt_fallback(
+        &self,
+        supported: ColorSupport,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        let is_open = self.spec.fmt_open(supported, true, f)?;
+        self.value.fmt_fallback(supported, f)?;
+        if is_open {
+            write!(f, "\x1B[0m")?;
+        }
+        Ok(())
+    }
+
+    /// Aligns like the contained value.
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        self.value.default_alignment()
+    }
+}
+
+/// The attributes of a [`Spec`]. They're held without allocating, because a spec has
+/// at most one of each style and color.
+pub(crate) struct Attrs {
+    attrs: [Attr<'static>; Self::MAX_LEN],
+    len: usize,
+}
+
+impl Attrs {
+    /// Every style, the text color, and the background color.
+    const MAX_LEN: usize = Styles::ALL.len() + 2;
+}
+
+impl Deref for Attrs {
+    type Target = [Attr<'static>];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.attrs[..self.len]
+    }
+}
+
+impl<D: DisplayWithBackend> DisplayWithBackend for Styled<D> {
+    /// Sends all colors and styles to the backend as a single group.
+    fn fmt_backend(
+        &self,
+        backend: &mut dyn Backend,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        backend::fmt_attrs(backend, &self.spec.attrs(), f, |backend, f| {
+            self.value.fmt_backend(backend, f)
+        })
+    }
+}
+
+impl<D: DisplayWithExact + DisplayWithFallback> fmt::Display for Styled<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        crate::display::fmt_configured(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Stylize;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::last_fg_wins(Spec::new().red().green(), "\x1B[32mx\x1B[0m")]
+    #[case::last_bg_wins(Spec::new().on_red().on_rgb(1, 2, 3), "\x1B[48;2;1;2;3mx\x1B[0m")]
+    #[case::styles_once(Spec::new().bold().bold(), "\x1B[1mx\x1B[0m")]
+    #[case::combined(Spec::new().blue().italic().on_eight_bit(7).bold(), "\x1B[1;3;34;48;5;7mx\x1B[0m")]
+    #[case::empty(Spec::new(), "x")]
+    fn test_exact(#[case] spec: Spec, #[case] expected: &str) {
+        let styled = "x".with_spec(spec).display_exact(ColorSupport::True);
+        assert_eq!(styled.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case(ColorSupport::True, "\x1B[1;38;2;255;0;0mx\x1B[0m")]
+    #[case(ColorSupport::EightBit, "\x1B[1;38;5;196mx\x1B[0m")]
+    #[case(ColorSupport::Simple, "\x1B[1;91mx\x1B[0m")]
+    #[case(ColorSupport::None, "x")]
+    fn test_fallback(#[case] supported: ColorSupport, #[case] expected: &str) {
+        let styled = "x".styled().green().bold().rgb(0xFF, 0, 0);
+        assert_eq!(styled.display_fallback(supported).to_string(), expected);
+    }
+
+    #[test]
+    fn test_exact_drops_unsupported_colors() {
+        let styled = "x".styled().rgb(0xFF, 0, 0).on_blue().underline();
+        assert_eq!(
+            styled.display_exact(ColorSupport::Simple).to_string(),
+            "\x1B[4;44mx\x1B[0m"
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::ColorLevel;
+use crate::{Capabilities, ColorLevel, Styles};
 use std::fmt;
 
 /// Marks a type as being able to represent a style.
@@ -26,4 +26,19 @@ pub trait Style {
     fn level(&self) -> ColorLevel {
         ColorLevel::Simple
     }
+    /// Gets the terminal capabilities that are required to show this style, in addition
+    /// to color support. See [`Capabilities`].
+    fn capability(&self) -> Capabilities {
+        Capabilities::NONE
+    }
+    /// Gets a style that can be shown instead when the required capabilities are
+    /// missing. This is only used when converting to supported styles.
+    fn substitute(&self) -> Option<Styles> {
+        None
+    }
+    /// Gets the built-in style that this style is, if it is one. Renderers that don't
+    /// write escape sequences, like HTML, can only show built-in styles.
+    fn to_styles(&self) -> Option<Styles> {
+        None
+    }
 }
@@ -0,0 +1,164 @@
+use crate::display::FmtFn;
+use crate::{ColorSupport, Spec};
+use std::fmt;
+use std::io;
+
+/// Wraps a writer, and applies the colors and styles of a [`Spec`] to everything
+/// written to it.
+///
+/// The style is closed before every line break and reopened on the next line, so
+/// pagers and line-based tools don't show colors bleeding into other lines. Empty
+/// lines are written without any escape sequences. By default, the color support that
+/// is configured when the writer is created is used, like when displaying a value.
+///
+/// Call [`finish`](Self::finish) after writing everything, to close the style if the
+/// last line doesn't end with a line break.
+///
+/// # Example
+///
+/// ```rust
+/// use chromaterm::{ColorSupport, Spec, StyledWriter};
+/// use std::io::Write;
+///
+/// let spec = Spec::new().red().bold();
+/// let mut writer = StyledWriter::new(Vec::new(), spec).with_support(ColorSupport::Simple);
+/// writeln!(writer, "first\n\nsecond").unwrap();
+/// assert_eq!(
+///     writer.finish().unwrap(),
+///     b"\x1B[1;31mfirst\x1B[0m\n\n\x1B[1;31msecond\x1B[0m\n",
+/// );
+/// ```
+#[derive(Debug)]
+pub struct StyledWriter<W: io::Write> {
+    inner: W,
+    spec: Spec,
+    /// The escape sequence that opens the style. It's empty if nothing is shown.
+    prefix: String,
+    /// If the style was written on the current line, and needs to be closed.
+    is_open: bool,
+}
+
+impl<W: io::Write> StyledWriter<W> {
+    /// Wraps the writer with the spec, using the configured color support.
+    pub fn new(inner: W, spec: Spec) -> Self {
+        let supported = crate::config::get_color_support();
+        let fallback = crate::config::get_convert_to_supported();
+        Self {
+            inner,
+            spec,
+            prefix: prefix(spec, supported, fallback),
+            is_open: false,
+        }
+    }
+
+    /// Uses the color support instead of the configured color support. Unsupported
+    /// colors are converted to supported colors.
+    pub fn with_support(mut self, supported: ColorSupport) -> Self {
+        self.prefix = prefix(self.spec, supported, true);
+        self
+    }
+
+    /// Gets a reference to the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the wrapped writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Closes the style if it's open, flushes, and unwraps the writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.close()?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    /// Writes the escape sequence that opens the style, if it isn't open.
+    fn open(&mut self) -> io::Result<()> {
+        if self.is_open {
+            return Ok(());
+        }
+        self.inner.write_all(self.prefix.as_bytes())?;
+        self.is_open = !self.prefix.is_empty();
+        Ok(())
+    }
+
+    /// Writes the escape sequence that closes the style, if it's open.
+    fn close(&mut self) -> io::Result<()> {
+        if self.is_open {
+            self.inner.write_all(b"\x1B[0m")?;
+            self.is_open = false;
+        }
+        Ok(())
+    }
+}
+
+/// Gets the escape sequence that opens the spec's style.
+fn prefix(spec: Spec, supported: ColorSupport, fallback: bool) -> String {
+    FmtFn(|f: &mut fmt::Formatter<'_>| spec.fmt_open(supported, fallback, f).map(|_| ()))
+        .to_string()
+}
+
+impl<W: io::Write> io::Write for StyledWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for line in buf.split_inclusive(|&byte| byte == b'\n') {
+            let (text, end): (&[u8], &[u8]) = if line.ends_with(b"\r\n") {
+                line.split_at(line.len() - 2)
+            } else if line.ends_with(b"\n") {
+                line.split_at(line.len() - 1)
+            } else {
+                (line, b"")
+            };
+            if !text.is_empty() {
+                self.open()?;
+                self.inner.write_all(text)?;
+            }
+            if !end.is_empty() {
+                self.close()?;
+                self.inner.write_all(end)?;
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+    use std::io::Write;
+
+    #[rstest]
+    #[case(&["error"], "\x1B[31merror\x1B[0m")]
+    #[case(&["a\nb\n"], "\x1B[31ma\x1B[0m\n\x1B[31mb\x1B[0m\n")]
+    #[case(&["a\r\n\n"], "\x1B[31ma\x1B[0m\r\n\n")]
+    #[case(&["a", "b\n", "c"], "\x1B[31mab\x1B[0m\n\x1B[31mc\x1B[0m")]
+    #[case(&["\n", "\n"], "\n\n")]
+    fn test_writes(#[case] writes: &[&str], #[case] expected: &str) {
+        let mut writer =
+            StyledWriter::new(Vec::new(), Spec::new().red()).with_support(ColorSupport::True);
+        for write in writes {
+            writer.write_all(write.as_bytes()).unwrap();
+        }
+        let written = writer.finish().unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(ColorSupport::None, "a\n")]
+    #[case(ColorSupport::Simple, "\x1B[1;91ma\x1B[0m\n")]
+    #[case(ColorSupport::True, "\x1B[1;38;2;255;0;0ma\x1B[0m\n")]
+    fn test_support(#[case] supported: ColorSupport, #[case] expected: &str) {
+        let spec = Spec::new().rgb(255, 0, 0).bold();
+        let mut writer = StyledWriter::new(Vec::new(), spec).with_support(supported);
+        writeln!(writer, "a").unwrap();
+        let written = writer.finish().unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), expected);
+    }
+}
@@ -1,5 +1,18 @@
-use crate::{ColorSupport, DisplayWithExact, DisplayWithFallback, Style};
-use std::fmt;
+use crate::backend::{self, Attr, Backend, DisplayWithBackend};
+use crate::display::FmtFn;
+use crate::write_to::{self, WriteTo};
+use crate::{ColorSupport, DisplayWithExact, DisplayWithFallback, Style, Styles};
+use std::{fmt, io};
+
+/// What is shown for a style when falling back to what is supported.
+enum Fallback {
+    /// The style itself.
+    Style,
+    /// The style's substitute.
+    Substitute(Styles),
+    /// Nothing.
+    Nothing,
+}
 
 /// Wraps a value that can be colorized.
 pub struct Styler<S: Style, D> {
@@ -13,25 +26,52 @@ impl<S: Style, D> Styler<S, D> {
         Self { style, value }
     }
 
-    /// Displays the contained value, including the style if there's *any* color
-    /// support.
+    /// Reapplies the colors and styles on every line. See [`PerLine`](crate::PerLine).
+    pub fn per_line(self) -> crate::PerLine<Self> {
+        crate::PerLine::new(self)
+    }
+
+    /// Checks if the style is supported. It's supported if there's *any* color support.
+    #[inline]
+    fn is_supported(&self, supported: ColorSupport) -> bool {
+        let level = self.style.level();
+        let level: ColorSupport = level.into();
+        supported >= level
+    }
+
+    /// Chooses what to show when falling back to what is supported. The style is
+    /// shown if there's *any* color support, or if colors are shown as styles with a
+    /// [`Monochrome`](crate::Monochrome) strategy. If the terminal doesn't have the
+    /// capabilities the style requires, the style's substitute is used instead.
+    fn fallback(&self, supported: ColorSupport) -> Fallback {
+        let is_supported =
+            self.is_supported(supported) || crate::monochrome::monochrome_for(supported).is_on();
+        let capabilities = crate::config::get_capabilities();
+        if !is_supported {
+            return Fallback::Nothing;
+        }
+        if capabilities.contains(self.style.capability()) {
+            return Fallback::Style;
+        }
+        self.style
+            .substitute()
+            .filter(|substitute| capabilities.contains(substitute.capability()))
+            .map_or(Fallback::Nothing, Fallback::Substitute)
+    }
+
+    /// Displays the contained value, including the style, if there is one.
     fn fmt_impl<F: FnOnce(&mut fmt::Formatter<'_>) -> Result<(), fmt::Error>>(
-        &self,
-        supported: ColorSupport,
+        style: Option<&dyn Style>,
         f: &mut fmt::Formatter<'_>,
         wrapped: F,
     ) -> Result<(), fmt::Error> {
-        let level = self.style.level();
-        let level: ColorSupport = level.into();
-        let is_supported = supported >= level;
-        if is_supported {
+        if let Some(style) = style {
             write!(f, "\x1B[")?;
-            self.style.fmt_style(f)?;
+            style.fmt_style(f)?;
             write!(f, "m")?;
         }
-        // self.value.fmt_exact(supported, f)?;
         wrapped(f)?;
-        if is_supported {
+        if style.is_some() {
             write!(f, "\x1B[0m")?;
         }
         Ok(())
@@ -40,38 +80,96 @@ impl<S: Style, D> Styler<S, D> {
 
 impl<S: Style, D: DisplayWithExact> DisplayWithExact for Styler<S, D> {
     /// Displays the contained value, including the style if there's *any* color
-    /// support.
+    /// support and the terminal has the capabilities the style requires.
     #[inline]
     fn fmt_exact(
         &self,
         supported: ColorSupport,
         f: &mut fmt::Formatter<'_>,
     ) -> Result<(), fmt::Error> {
-        self.fmt_impl(supported, f, |f| self.value.fmt_exact(supported, f))
+        let capabilities = crate::config::get_capabilities();
+        let is_supported =
+            self.is_supported(supported) && capabilities.contains(self.style.capability());
+        let style = is_supported.then_some(&self.style as &dyn Style);
+        Self::fmt_impl(style, f, |f| self.value.fmt_exact(supported, f))
+    }
+
+    /// Aligns like the contained value.
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        self.value.default_alignment()
     }
 }
 
 impl<S: Style, D: DisplayWithFallback> DisplayWithFallback for Styler<S, D> {
     /// Displays the contained value, including the style if there's *any* color
-    /// support.
+    /// support, or if colors are shown as styles with a [`Monochrome`](crate::Monochrome)
+    /// strategy. If the terminal doesn't have the capabilities the style requires, the
+    /// style's substitute is used instead.
     #[inline]
     fn fmt_fallback(
         &self,
         supported: ColorSupport,
         f: &mut fmt::Formatter<'_>,
     ) -> Result<(), fmt::Error> {
-        self.fmt_impl(supported, f, |f| self.value.fmt_fallback(supported, f))
+        let fallback = self.fallback(supported);
+        let style: Option<&dyn Style> = match &fallback {
+            Fallback::Style => Some(&self.style),
+            Fallback::Substitute(substitute) => Some(substitute),
+            Fallback::Nothing => None,
+        };
+        Self::fmt_impl(style, f, |f| self.value.fmt_fallback(supported, f))
+    }
+
+    /// Aligns like the contained value.
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        self.value.default_alignment()
+    }
+}
+
+impl<S: Style, D: DisplayWithBackend> DisplayWithBackend for Styler<S, D> {
+    /// Sends the style to the backend. Only built-in styles can be sent, so custom
+    /// styles are left out.
+    fn fmt_backend(
+        &self,
+        backend: &mut dyn Backend,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        let attrs: &[Attr<'_>] = match self.style.to_styles() {
+            Some(style) => &[Attr::Style(style)],
+            None => &[],
+        };
+        backend::fmt_attrs(backend, attrs, f, |backend, f| {
+            self.value.fmt_backend(backend, f)
+        })
+    }
+}
+
+impl<S: Style, D: WriteTo> WriteTo for Styler<S, D> {
+    /// Writes the contained value like [`DisplayWithFallback`], but without formatting.
+    /// Custom styles are still formatted.
+    fn write_to<W: io::Write + ?Sized>(
+        &self,
+        w: &mut W,
+        supported: ColorSupport,
+    ) -> io::Result<()> {
+        let style = match self.fallback(supported) {
+            Fallback::Style => self.style.to_styles(),
+            Fallback::Substitute(substitute) => Some(substitute),
+            Fallback::Nothing => return self.value.write_to(w, supported),
+        };
+        match style {
+            Some(style) => w.write_all(write_to::style_sequence(style))?,
+            None => write!(w, "\x1B[{}m", FmtFn(|f| self.style.fmt_style(f)))?,
+        }
+        self.value.write_to(w, supported)?;
+        w.write_all(write_to::RESET)
     }
 }
 
 impl<S: Style, D: DisplayWithExact + DisplayWithFallback> fmt::Display for Styler<S, D> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
-        let support = crate::config::get_color_support();
-        let should_fallback = crate::config::get_convert_to_supported();
-        if should_fallback {
-            self.fmt_fallback(support, f)
-        } else {
-            self.fmt_exact(support, f)
-        }
+        crate::display::fmt_configured(self, f)
     }
 }
@@ -1,5 +1,5 @@
 //! Text styles.
-use crate::Style;
+use crate::{Capabilities, Style};
 use std::fmt;
 
 /// The bold style.
@@ -17,28 +17,74 @@ pub struct Underline;
 /// The strike style.
 pub struct Strike;
 
+/// The reverse style, which swaps the text and background colors.
+pub struct Reverse;
+
+/// The blink style.
+pub struct Blink;
+
+/// The curly underline style. Falls back to a normal underline.
+pub struct CurlyUnderline;
+
+/// The hidden style, which hides the text while keeping its space.
+pub struct Hidden;
+
 macro_rules! impl_style {
-    ($( ( $name:ident, $value:literal ) ),+) => {
+    ($( ( $name:ident, $value:literal, $capability:ident, $substitute:expr ) ),+) => {
 
         $(
             impl Style for $name {
                 fn fmt_style(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
                     write!(f, $value)
                 }
+
+                fn capability(&self) -> Capabilities {
+                    Capabilities::$capability
+                }
+
+                fn substitute(&self) -> Option<Styles> {
+                    $substitute
+                }
+
+                fn to_styles(&self) -> Option<Styles> {
+                    Some(Styles::$name)
+                }
             }
         )+
 
         /// Unifies styles into one value.
+        #[derive(Debug, PartialEq, Eq, Copy, Clone)]
         pub enum Styles {
             $($name),+
         }
 
+        impl Styles {
+            /// All styles, in the order they are written.
+            pub(crate) const ALL: &[Self] = &[$(Self::$name),+];
+        }
+
         impl Style for Styles {
             fn fmt_style(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
                 match self {
                     $(Self::$name => $name.fmt_style(f)),+
                 }
             }
+
+            fn capability(&self) -> Capabilities {
+                match self {
+                    $(Self::$name => $name.capability()),+
+                }
+            }
+
+            fn substitute(&self) -> Option<Styles> {
+                match self {
+                    $(Self::$name => $name.substitute()),+
+                }
+            }
+
+            fn to_styles(&self) -> Option<Styles> {
+                Some(*self)
+            }
         }
 
         $(
@@ -58,9 +104,88 @@ macro_rules! impl_style {
 }
 
 impl_style!(
-    (Bold, "1"),
-    (Dim, "2"),
-    (Italic, "3"),
-    (Underline, "4"),
-    (Strike, "9")
+    (Bold, "1", NONE, None),
+    (Dim, "2", NONE, None),
+    (Italic, "3", ITALIC, None),
+    (Underline, "4", NONE, None),
+    (Strike, "9", STRIKETHROUGH, None),
+    (Reverse, "7", NONE, None),
+    (Blink, "5", BLINK, None),
+    (
+        CurlyUnderline,
+        "4:3",
+        CURLY_UNDERLINE,
+        Some(Styles::Underline)
+    ),
+    (Hidden, "8", NONE, None)
 );
+
+impl Styles {
+    /// Resolves the style that should be shown with the capabilities. If `fallback`,
+    /// the substitute is used when the style isn't supported.
+    pub(crate) fn resolve(&self, capabilities: Capabilities, fallback: bool) -> Option<Self> {
+        if capabilities.contains(self.capability()) {
+            return Some(*self);
+        }
+        self.substitute()
+            .filter(|substitute| fallback && capabilities.contains(substitute.capability()))
+    }
+
+    /// The bit that represents this style in a [`StyleSet`].
+    #[inline]
+    const fn bit(&self) -> u16 {
+        1 << (*self as u16)
+    }
+}
+
+/// A set of styles. Inserting a style that is already in the set has no effect.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct StyleSet(u16);
+
+impl StyleSet {
+    /// Creates an empty set.
+    #[inline]
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Adds a style to the set.
+    #[inline]
+    pub fn insert<S: Into<Styles>>(&mut self, style: S) {
+        self.0 |= style.into().bit();
+    }
+
+    /// Removes a style from the set.
+    #[inline]
+    pub fn remove<S: Into<Styles>>(&mut self, style: S) {
+        self.0 &= !style.into().bit();
+    }
+
+    /// Checks if the style is in the set.
+    #[inline]
+    pub fn contains<S: Into<Styles>>(&self, style: S) -> bool {
+        self.0 & style.into().bit() != 0
+    }
+
+    /// Checks if the set has no styles.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Iterates over the styles in the set.
+    pub fn iter(&self) -> impl Iterator<Item = Styles> + '_ {
+        Styles::ALL
+            .iter()
+            .copied()
+            .filter(|style| self.contains(*style))
+    }
+}
+
+impl<S: Into<Styles>> FromIterator<S> for StyleSet {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut set = Self::new();
+        iter.into_iter().for_each(|style| set.insert(style));
+        set
+    }
+}
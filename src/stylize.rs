@@ -1,4 +1,5 @@
-use crate::{DisplayWithExact, DisplayWithFallback, Style, Styler, styles};
+use crate::theme::{Role, Themed};
+use crate::{DisplayWithExact, DisplayWithFallback, Link, Spec, Style, Styled, Styler, styles};
 
 /// The main trait to allow a type to be styled.
 pub trait Stylize: DisplayWithExact + DisplayWithFallback + Sized {
@@ -31,6 +32,50 @@ pub trait Stylize: DisplayWithExact + DisplayWithFallback + Sized {
     fn strike(self) -> Styler<styles::Strike, Self> {
         self.style(styles::Strike)
     }
+
+    /// Swaps the text and background colors.
+    fn reverse(self) -> Styler<styles::Reverse, Self> {
+        self.style(styles::Reverse)
+    }
+
+    /// Makes the text blink. Requires [`Capabilities::BLINK`](crate::Capabilities::BLINK).
+    fn blink(self) -> Styler<styles::Blink, Self> {
+        self.style(styles::Blink)
+    }
+
+    /// Underlines the text with a curly line. Requires
+    /// [`Capabilities::CURLY_UNDERLINE`](crate::Capabilities::CURLY_UNDERLINE), and falls
+    /// back to a normal underline.
+    fn curly_underline(self) -> Styler<styles::CurlyUnderline, Self> {
+        self.style(styles::CurlyUnderline)
+    }
+
+    /// Hides the text, while keeping the space it takes up.
+    fn hidden(self) -> Styler<styles::Hidden, Self> {
+        self.style(styles::Hidden)
+    }
+
+    /// Starts a chain where later colors *replace* earlier colors instead of wrapping
+    /// them. See [`Styled`].
+    fn styled(self) -> Styled<Self> {
+        self.with_spec(Spec::new())
+    }
+
+    /// Applies all colors and styles from a [`Spec`].
+    fn with_spec(self, spec: Spec) -> Styled<Self> {
+        Styled::new(self, spec)
+    }
+
+    /// Styles the text according to its role in the global theme. See
+    /// [`config::use_theme`](crate::config::use_theme).
+    fn role(self, role: Role) -> Themed<Self> {
+        Themed::new(self, role)
+    }
+
+    /// Makes the text a hyperlink to the URL. See [`Link`].
+    fn link<U: AsRef<str>>(self, url: U) -> Link<U, Self> {
+        Link::new(self, url)
+    }
 }
 
 impl<D: DisplayWithExact + DisplayWithFallback> Stylize for D {}
@@ -0,0 +1,367 @@
+//! Rendering text with ANSI escape sequences, or colorized and styled values, as SVG.
+//!
+//! # Example
+//!
+//! ```rust
+//! use chromaterm::prelude::*;
+//! use chromaterm::svg::Svg;
+//!
+//! let svg = Svg::new().render("\x1B[1;32mok\x1B[0m: 3 tests");
+//! assert!(svg.starts_with("<svg "));
+//! assert!(svg.contains(">ok</tspan>"));
+//!
+//! let svg = Svg::new().render_value(&"ok".green().bold());
+//! assert!(svg.contains(">ok</tspan>"));
+//! ```
+use crate::colors::{Palette, Simple, True};
+use crate::display::FmtFn;
+use crate::html::Escaper;
+use crate::{Color, ColorSupport, DisplayWithExact, Spec, Styles};
+use std::fmt::{self, Write};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Settings for rendering SVG images of terminal output.
+///
+/// Text is laid out on a grid of monospace cells. Each line is a `<text>` element, and
+/// each run of styled text is a `<tspan>` that is positioned on the grid, so the grid
+/// stays aligned even if the font is missing some characters. Background colors are
+/// drawn as `<rect>` elements behind the text.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Svg {
+    palette: Palette,
+    font_family: String,
+    font_size: u16,
+    fg: True,
+    bg: True,
+}
+
+impl Svg {
+    /// The default fonts, which are common monospace fonts.
+    pub const DEFAULT_FONT_FAMILY: &str =
+        "ui-monospace, SFMono-Regular, Menlo, Consolas, 'DejaVu Sans Mono', monospace";
+    /// The default font size, in pixels.
+    pub const DEFAULT_FONT_SIZE: u16 = 14;
+
+    /// Creates settings with the default palette and font. The text is the palette's
+    /// white, and the background is the palette's black.
+    pub fn new() -> Self {
+        let palette = Palette::default();
+        Self {
+            palette,
+            font_family: Self::DEFAULT_FONT_FAMILY.to_owned(),
+            font_size: Self::DEFAULT_FONT_SIZE,
+            fg: palette.get(Simple::White),
+            bg: palette.get(Simple::Black),
+        }
+    }
+
+    /// Sets the palette that the simple colors are shown with.
+    pub fn with_palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Sets the font family, as a CSS font family list, and the font size in pixels.
+    pub fn with_font<F: Into<String>>(mut self, family: F, size: u16) -> Self {
+        self.font_family = family.into();
+        self.font_size = size;
+        self
+    }
+
+    /// Sets the default text and background colors.
+    pub fn with_colors(mut self, fg: True, bg: True) -> Self {
+        self.fg = fg;
+        self.bg = bg;
+        self
+    }
+
+    /// Renders text that contains ANSI escape sequences. SGR sequences are shown, and all
+    /// other escape sequences are ignored.
+    pub fn render(&self, text: &str) -> String {
+        let mut lines: Vec<Vec<(Spec, &str)>> = vec![Vec::new()];
+        for (spec, text) in crate::ansi::parse(text) {
+            for (index, part) in text.split('\n').enumerate() {
+                if index > 0 {
+                    lines.push(Vec::new());
+                }
+                if let Some(line) = lines.last_mut() {
+                    line.push((spec, part));
+                }
+            }
+        }
+        let mut svg = String::new();
+        // NOTE Writing to a String never fails.
+        let _ = self.write_svg(&lines, &mut svg);
+        svg
+    }
+
+    /// Renders a colorized or styled value, as if it was shown by a terminal with true
+    /// color support and the configured capabilities.
+    pub fn render_value<D: DisplayWithExact + ?Sized>(&self, value: &D) -> String {
+        let render = FmtFn(|f: &mut fmt::Formatter<'_>| value.fmt_exact(ColorSupport::True, f));
+        self.render(&render.to_string())
+    }
+
+    /// The width of a cell.
+    #[inline]
+    fn cell_width(&self) -> f64 {
+        f64::from(self.font_size) * 0.6
+    }
+
+    /// The height of a line.
+    #[inline]
+    fn line_height(&self) -> f64 {
+        f64::from(self.font_size) * 1.2
+    }
+
+    /// The space around the text.
+    #[inline]
+    fn padding(&self) -> f64 {
+        f64::from(self.font_size) * 0.5
+    }
+
+    /// Writes the SVG for the lines of spans.
+    fn write_svg<W: Write>(&self, lines: &[Vec<(Spec, &str)>], w: &mut W) -> fmt::Result {
+        let columns = lines
+            .iter()
+            .map(|line| {
+                line.iter()
+                    .fold(0, |column, (_, text)| column + clean(text, column).width())
+            })
+            .max()
+            .unwrap_or(0);
+        let width = px(self.padding() * 2.0 + self.cell_width() * columns as f64);
+        let height = px(self.padding() * 2.0 + self.line_height() * lines.len() as f64);
+        write!(
+            w,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\" font-family=\""
+        )?;
+        Escaper(&mut *w).write_str(&self.font_family)?;
+        write!(w, "\" font-size=\"{}\">", self.font_size)?;
+        write!(
+            w,
+            "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+            Hex(self.bg)
+        )?;
+        for (row, line) in lines.iter().enumerate() {
+            self.write_backgrounds(row, line, w)?;
+        }
+        for (row, line) in lines.iter().enumerate() {
+            self.write_line(row, line, w)?;
+        }
+        w.write_str("</svg>")
+    }
+
+    /// Writes the background rectangles for a line.
+    fn write_backgrounds<W: Write>(
+        &self,
+        row: usize,
+        line: &[(Spec, &str)],
+        w: &mut W,
+    ) -> fmt::Result {
+        let y = px(self.padding() + self.line_height() * row as f64);
+        let mut column = 0;
+        for (spec, text) in line {
+            let columns = clean(text, column).width();
+            let (_, bg) = self.colors(spec);
+            if bg != self.bg && columns > 0 {
+                let x = px(self.padding() + self.cell_width() * column as f64);
+                write!(
+                    w,
+                    "<rect x=\"{x}\" y=\"{y}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    px(self.cell_width() * columns as f64),
+                    px(self.line_height()),
+                    Hex(bg)
+                )?;
+            }
+            column += columns;
+        }
+        Ok(())
+    }
+
+    /// Writes the text of a line.
+    fn write_line<W: Write>(&self, row: usize, line: &[(Spec, &str)], w: &mut W) -> fmt::Result {
+        // NOTE The baseline is placed so that the text is roughly centered in the line.
+        let y = px(self.padding() + self.line_height() * row as f64 + f64::from(self.font_size));
+        write!(
+            w,
+            "<text y=\"{y}\" fill=\"{}\" xml:space=\"preserve\">",
+            Hex(self.fg)
+        )?;
+        let mut column = 0;
+        for (spec, text) in line {
+            let text = clean(text, column);
+            let columns = text.width();
+            let styles = spec.styles();
+            if columns == 0 || styles.contains(Styles::Hidden) {
+                column += columns;
+                continue;
+            }
+            let x = px(self.padding() + self.cell_width() * column as f64);
+            write!(w, "<tspan x=\"{x}\"")?;
+            let (fg, _) = self.colors(spec);
+            if fg != self.fg {
+                write!(w, " fill=\"{}\"", Hex(fg))?;
+            }
+            if styles.contains(Styles::Bold) {
+                w.write_str(" font-weight=\"bold\"")?;
+            }
+            if styles.contains(Styles::Italic) {
+                w.write_str(" font-style=\"italic\"")?;
+            }
+            if styles.contains(Styles::Dim) {
+                w.write_str(" fill-opacity=\"0.5\"")?;
+            }
+            let underline =
+                styles.contains(Styles::Underline) || styles.contains(Styles::CurlyUnderline);
+            let decorations = [
+                (underline, "underline"),
+                (styles.contains(Styles::Strike), "line-through"),
+            ];
+            let mut decorations = decorations
+                .into_iter()
+                .filter_map(|(is_on, decoration)| is_on.then_some(decoration))
+                .peekable();
+            if decorations.peek().is_some() {
+                let decorations: Vec<_> = decorations.collect();
+                write!(w, " text-decoration=\"{}\"", decorations.join(" "))?;
+            }
+            w.write_str(">")?;
+            Escaper(&mut *w).write_str(&text)?;
+            w.write_str("</tspan>")?;
+            column += columns;
+        }
+        w.write_str("</text>")
+    }
+
+    /// Gets the text and background colors for a spec.
+    fn colors(&self, spec: &Spec) -> (True, True) {
+        let to_true = |color| {
+            let (r, g, b) = self.palette.rgb(&color);
+            True::from_rgb(r, g, b)
+        };
+        let fg = spec.fg().map_or(self.fg, to_true);
+        let bg = spec.bg().map_or(self.bg, to_true);
+        if spec.styles().contains(Styles::Reverse) {
+            (bg, fg)
+        } else {
+            (fg, bg)
+        }
+    }
+}
+
+impl Default for Svg {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The number of columns between tab stops.
+const TAB_WIDTH: usize = 8;
+
+/// Removes control characters, like carriage returns, that can't be shown, and expands
+/// tabs to spaces up to the next tab stop. The text starts at the `column`.
+fn clean(text: &str, mut column: usize) -> std::borrow::Cow<'_, str> {
+    if !text.chars().any(char::is_control) {
+        return std::borrow::Cow::Borrowed(text);
+    }
+    let mut cleaned = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\t' => {
+                let spaces = TAB_WIDTH - column % TAB_WIDTH;
+                cleaned.extend(std::iter::repeat_n(' ', spaces));
+                column += spaces;
+            }
+            c if c.is_control() => {}
+            c => {
+                cleaned.push(c);
+                column += c.width().unwrap_or(0);
+            }
+        }
+    }
+    std::borrow::Cow::Owned(cleaned)
+}
+
+/// Rounds a number of pixels to 2 decimal places.
+#[inline]
+fn px(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Displays a color as a hex code.
+struct Hex(True);
+
+impl fmt::Display for Hex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let (r, g, b) = self.0.rgb_u8();
+        write!(f, "#{r:02x}{g:02x}{b:02x}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Colorize;
+
+    /// Helper to render with a font that gives round numbers.
+    fn render(text: &str) -> String {
+        Svg::new().with_font("mono", 10).render(text)
+    }
+
+    #[test]
+    fn test_render_plain() {
+        assert_eq!(
+            render("a<b\nc"),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"28\" height=\"34\" \
+             viewBox=\"0 0 28 34\" font-family=\"mono\" font-size=\"10\">\
+             <rect width=\"100%\" height=\"100%\" fill=\"#000000\"/>\
+             <text y=\"15\" fill=\"#aaaaaa\" xml:space=\"preserve\">\
+             <tspan x=\"5\">a&lt;b</tspan></text>\
+             <text y=\"27\" fill=\"#aaaaaa\" xml:space=\"preserve\">\
+             <tspan x=\"5\">c</tspan></text></svg>"
+        );
+    }
+
+    #[test]
+    fn test_render_styles() {
+        let svg = render("\x1B[1;3;4;31mx\x1B[0m\x1B[2;9my");
+        assert!(svg.contains(
+            "<tspan x=\"5\" fill=\"#aa0000\" font-weight=\"bold\" font-style=\"italic\" \
+             text-decoration=\"underline\">x</tspan>"
+        ));
+        assert!(svg.contains(
+            "<tspan x=\"11\" fill-opacity=\"0.5\" text-decoration=\"line-through\">y</tspan>"
+        ));
+    }
+
+    #[test]
+    fn test_render_backgrounds() {
+        let svg = render("a\x1B[44m\u{4F60}\x1B[7mb");
+        assert!(
+            svg.contains("<rect x=\"11\" y=\"5\" width=\"12\" height=\"12\" fill=\"#0000aa\"/>")
+        );
+        assert!(
+            svg.contains("<rect x=\"23\" y=\"5\" width=\"6\" height=\"12\" fill=\"#aaaaaa\"/>")
+        );
+        assert!(svg.contains("<tspan x=\"23\" fill=\"#0000aa\">b</tspan>"));
+    }
+
+    #[test]
+    fn test_render_tabs() {
+        let svg = render("ab\tc\x1B[31m\td\r");
+        assert!(svg.contains("<tspan x=\"5\">ab      c</tspan>"));
+        assert!(svg.contains("<tspan x=\"59\" fill=\"#aa0000\">       d</tspan>"));
+        assert!(svg.contains("width=\"112\""));
+    }
+
+    #[test]
+    fn test_render_value() {
+        let palette = Palette::default().with(Simple::Red, True::from_rgb(0xCD, 0, 0));
+        let svg = Svg::new()
+            .with_palette(palette)
+            .render_value(&"error".red());
+        assert!(svg.contains("<tspan x=\"7\" fill=\"#cd0000\">error</tspan>"));
+    }
+}
@@ -0,0 +1,278 @@
+//! Semantic roles, and themes that map them to colors and styles.
+//!
+//! # Example
+//!
+//! ```rust
+//! use chromaterm::prelude::*;
+//! use chromaterm::theme::{Role, Theme};
+//! use chromaterm::{ColorSupport, Spec};
+//!
+//! let theme = Theme::default().with(Role::Error, Spec::new().magenta().bold());
+//! chromaterm::config::use_theme(theme);
+//!
+//! let error = "oh no".role(Role::Error);
+//! assert_eq!(
+//!     error.display_exact(ColorSupport::Simple).to_string(),
+//!     "\x1B[1;35moh no\x1B[0m",
+//! );
+//! ```
+use crate::backend::{self, Backend, DisplayWithBackend};
+use crate::{ColorSupport, DisplayWithExact, DisplayWithFallback, Spec};
+use std::fmt;
+
+/// A semantic role for text. A [`Theme`] decides how each role looks.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum Role {
+    /// Errors and failures.
+    Error,
+    /// Warnings.
+    Warn,
+    /// Informational messages.
+    Info,
+    /// Debugging output.
+    Debug,
+    /// Successful outcomes.
+    Success,
+    /// Headings and titles.
+    Heading,
+    /// Text of lesser importance.
+    Muted,
+    /// Links, like URLs and file paths.
+    Link,
+    /// Code, commands, and identifiers.
+    Code,
+}
+
+impl Role {
+    /// All roles.
+    pub const ALL: [Self; 9] = [
+        Self::Error,
+        Self::Warn,
+        Self::Info,
+        Self::Debug,
+        Self::Success,
+        Self::Heading,
+        Self::Muted,
+        Self::Link,
+        Self::Code,
+    ];
+
+    #[inline]
+    const fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Maps each [`Role`] to a [`Spec`].
+///
+/// The [`Default`] theme only uses the basic 16 colors, so that it looks reasonable
+/// with any color support.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Theme {
+    specs: [Spec; Role::ALL.len()],
+}
+
+impl Theme {
+    /// Creates a theme where no role has any colors or styles.
+    pub const fn plain() -> Self {
+        Self {
+            specs: [Spec::new(); Role::ALL.len()],
+        }
+    }
+
+    /// Gets the spec for the role.
+    #[inline]
+    pub fn get(&self, role: Role) -> Spec {
+        self.specs[role.index()]
+    }
+
+    /// Sets the spec for the role.
+    #[inline]
+    pub fn set(&mut self, role: Role, spec: Spec) {
+        self.specs[role.index()] = spec;
+    }
+
+    /// Sets the spec for the role, and returns the theme.
+    pub fn with(mut self, role: Role, spec: Spec) -> Self {
+        self.set(role, spec);
+        self
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::plain()
+            .with(Role::Error, Spec::new().bright_red().bold())
+            .with(Role::Warn, Spec::new().yellow())
+            .with(Role::Info, Spec::new().cyan())
+            .with(Role::Debug, Spec::new().bright_black())
+            .with(Role::Success, Spec::new().bright_green())
+            .with(Role::Heading, Spec::new().bold().underline())
+            .with(Role::Muted, Spec::new().dim())
+            .with(Role::Link, Spec::new().blue().underline())
+            .with(Role::Code, Spec::new().magenta())
+    }
+}
+
+/// Wraps a value with a [`Role`]. The role is looked up in the global theme each time
+/// the value is displayed, so changing the theme re-themes existing values.
+pub struct Themed<D> {
+    role: Role,
+    value: D,
+}
+
+impl<D> Themed<D> {
+    /// Wraps the displayable value with a role.
+    pub fn new(value: D, role: Role) -> Self {
+        Self { role, value }
+    }
+
+    /// Reapplies the colors and styles on every line. See [`PerLine`](crate::PerLine).
+    pub fn per_line(self) -> crate::PerLine<Self> {
+        crate::PerLine::new(self)
+    }
+
+    /// Displays the contained value with the role's spec from the global theme.
+    fn fmt_impl<F: FnOnce(&mut fmt::Formatter<'_>) -> Result<(), fmt::Error>>(
+        &self,
+        supported: ColorSupport,
+        fallback: bool,
+        f: &mut fmt::Formatter<'_>,
+        wrapped: F,
+    ) -> Result<(), fmt::Error> {
+        let spec = crate::config::get_theme().get(self.role);
+        let is_open = spec.fmt_open(supported, fallback, f)?;
+        wrapped(f)?;
+        if is_open {
+            write!(f, "\x1B[0m")?;
+        }
+        Ok(())
+    }
+}
+
+impl<D: DisplayWithExact> DisplayWithExact for Themed<D> {
+    /// Displays the contained value, including only the colors that are supported.
+    #[inline]
+    fn fmt_exact(
+        &self,
+        supported: ColorSupport,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        self.fmt_impl(supported, false, f, |f| self.value.fmt_exact(supported, f))
+    }
+
+    /// Aligns like the contained value.
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        self.value.default_alignment()
+    }
+}
+
+impl<D: DisplayWithFallback> DisplayWithFallback for Themed<D> {
+    /// Displays the contained value, and tries to fall back to colors that are
+    /// supported.
+    #[inline]
+    fn fmt_fallback(
+        &self,
+        supported: ColorSupport,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        self.fmt_impl(supported, true, f, |f| {
+            self.value.fmt_fallback(supported, f)
+        })
+    }
+
+    /// Aligns like the contained value.
+    #[inline]
+    fn default_alignment(&self) -> fmt::Alignment {
+        self.value.default_alignment()
+    }
+}
+
+impl<D: DisplayWithBackend> DisplayWithBackend for Themed<D> {
+    /// Sends the role's spec from the global theme to the backend.
+    fn fmt_backend(
+        &self,
+        backend: &mut dyn Backend,
+        f: &mut fmt::Formatter<'_>,
+    ) -> Result<(), fmt::Error> {
+        let spec = crate::config::get_theme().get(self.role);
+        backend::fmt_attrs(backend, &spec.attrs(), f, |backend, f| {
+            self.value.fmt_backend(backend, f)
+        })
+    }
+}
+
+impl<D: DisplayWithExact + DisplayWithFallback> fmt::Display for Themed<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        crate::display::fmt_configured(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Stylize;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Role::Error)]
+    #[case(Role::Warn)]
+    #[case(Role::Info)]
+    #[case(Role::Debug)]
+    #[case(Role::Success)]
+    #[case(Role::Heading)]
+    #[case(Role::Muted)]
+    #[case(Role::Link)]
+    #[case(Role::Code)]
+    fn test_get(#[case] role: Role) {
+        let spec = Spec::new().green().italic();
+        let theme = Theme::plain().with(role, spec);
+        for other in Role::ALL {
+            let expected = if other == role { spec } else { Spec::new() };
+            assert_eq!(theme.get(other), expected, "{other:?} should be looked up");
+        }
+    }
+
+    #[rstest]
+    #[case(Role::Error, "\x1B[1;91mx\x1B[0m")]
+    #[case(Role::Heading, "\x1B[1;4mx\x1B[0m")]
+    #[case(Role::Link, "\x1B[4;34mx\x1B[0m")]
+    fn test_default_theme(#[case] role: Role, #[case] expected: &str) {
+        let theme = Theme::default();
+        let value = "x".with_spec(theme.get(role));
+        assert_eq!(
+            value.display_exact(ColorSupport::Simple).to_string(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_use_theme() {
+        // NOTE This is the only test that changes the global theme, so that other tests
+        //      aren't affected, and the theme is reset at the end.
+        assert_eq!(
+            crate::config::get_theme(),
+            Theme::default(),
+            "the default theme should be used if none is set"
+        );
+        let error = "x".role(Role::Error);
+        let warn = "x".role(Role::Warn);
+        assert_eq!(
+            (&error).display_exact(ColorSupport::Simple).to_string(),
+            "\x1B[1;91mx\x1B[0m"
+        );
+        crate::config::use_theme(Theme::default().with(Role::Error, Spec::new().magenta()));
+        assert_eq!(
+            (&error).display_exact(ColorSupport::Simple).to_string(),
+            "\x1B[35mx\x1B[0m",
+            "existing values should be re-themed"
+        );
+        assert_eq!(
+            warn.display_exact(ColorSupport::Simple).to_string(),
+            "\x1B[33mx\x1B[0m",
+            "other roles should keep their spec"
+        );
+        crate::config::use_theme(Theme::default());
+    }
+}
@@ -0,0 +1,178 @@
+//! Rendering colorized and styled values as tmux format strings.
+//!
+//! tmux doesn't use escape sequences in its status line and other format strings.
+//! Instead, styles are written like `#[fg=colour208,bold]`. [`Tmux`] is a [`Backend`]
+//! that writes this syntax.
+//!
+//! # Example
+//!
+//! ```rust
+//! use chromaterm::prelude::*;
+//! use chromaterm::tmux::Tmux;
+//! use chromaterm::ColorSupport;
+//!
+//! let value = "#1 main".eight_bit(208).bold();
+//! assert_eq!(
+//!     value.display_with(Tmux::new(ColorSupport::EightBit)).to_string(),
+//!     "#[bold]#[fg=colour208]##1 main#[default]#[default]",
+//! );
+//! ```
+use crate::backend::{Attr, Backend};
+use crate::colors::Simple;
+use crate::{Color, ColorSupport, Colors, Spec, Styles};
+use std::fmt;
+
+/// A [`Backend`] that writes tmux format strings.
+///
+/// Like [`Ansi`](crate::backend::Ansi), unsupported colors are converted to supported
+/// colors, and each value is closed with `#[default]`, which resets all colors and
+/// styles. tmux converts colors for the terminal itself, so
+/// [`ColorSupport::True`] is usually the right choice. `#` in text is escaped, and
+/// hyperlinks are ignored.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Tmux {
+    support: ColorSupport,
+    /// For each open group, if anything was written.
+    open: Vec<bool>,
+}
+
+impl Tmux {
+    /// Creates a backend for the color support.
+    #[inline]
+    pub const fn new(support: ColorSupport) -> Self {
+        Self {
+            support,
+            open: Vec::new(),
+        }
+    }
+}
+
+/// Gets tmux's name for a style.
+const fn style_name(style: Styles) -> &'static str {
+    match style {
+        Styles::Bold => "bold",
+        Styles::Dim => "dim",
+        Styles::Italic => "italics",
+        Styles::Underline => "underscore",
+        Styles::Strike => "strikethrough",
+        Styles::Reverse => "reverse",
+        Styles::Blink => "blink",
+        Styles::CurlyUnderline => "curly-underscore",
+        Styles::Hidden => "hidden",
+    }
+}
+
+/// Gets tmux's name for a simple color.
+const fn simple_name(color: Simple) -> &'static str {
+    match color {
+        Simple::Black => "black",
+        Simple::Red => "red",
+        Simple::Green => "green",
+        Simple::Yellow => "yellow",
+        Simple::Blue => "blue",
+        Simple::Magenta => "magenta",
+        Simple::Cyan => "cyan",
+        Simple::White => "white",
+        Simple::BrightBlack => "brightblack",
+        Simple::BrightRed => "brightred",
+        Simple::BrightGreen => "brightgreen",
+        Simple::BrightYellow => "brightyellow",
+        Simple::BrightBlue => "brightblue",
+        Simple::BrightMagenta => "brightmagenta",
+        Simple::BrightCyan => "brightcyan",
+        Simple::BrightWhite => "brightwhite",
+    }
+}
+
+/// Writes a color the way tmux's `fg` and `bg` expect it.
+fn fmt_color(color: Colors, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+    match color {
+        Colors::Simple(simple) => f.write_str(simple_name(simple)),
+        Colors::EightBit(eight_bit) => write!(f, "colour{}", eight_bit.lookup()),
+        Colors::True(true_color) => {
+            let (r, g, b) = true_color.rgb_u8();
+            write!(f, "#{r:02x}{g:02x}{b:02x}")
+        }
+    }
+}
+
+impl Backend for Tmux {
+    fn open(&mut self, attrs: &[Attr<'_>], f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let spec = Spec::from_attrs(attrs).resolve(self.support, true);
+        if spec.is_empty() {
+            self.open.push(false);
+            return Ok(());
+        }
+        let mut separator = "";
+        f.write_str("#[")?;
+        for style in spec.styles().iter() {
+            write!(f, "{separator}{}", style_name(style))?;
+            separator = ",";
+        }
+        if let Some(color) = spec.fg() {
+            write!(f, "{separator}fg=")?;
+            fmt_color(color, f)?;
+            separator = ",";
+        }
+        if let Some(color) = spec.bg() {
+            write!(f, "{separator}bg=")?;
+            fmt_color(color, f)?;
+        }
+        f.write_str("]")?;
+        self.open.push(true);
+        Ok(())
+    }
+
+    fn close(&mut self, _attrs: &[Attr<'_>], f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        if self.open.pop().unwrap_or_default() {
+            f.write_str("#[default]")?;
+        }
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        for (index, part) in text.split('#').enumerate() {
+            if index > 0 {
+                f.write_str("##")?;
+            }
+            f.write_str(part)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Colorize, DisplayWithBackend, Stylize};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("x".bright_red(), ColorSupport::Simple, "#[fg=brightred]x#[default]")]
+    #[case("x".on_rgb(255, 0, 10), ColorSupport::True, "#[bg=#ff000a]x#[default]")]
+    #[case("x".on_rgb(255, 0, 10), ColorSupport::EightBit, "#[bg=colour196]x#[default]")]
+    #[case("x".eight_bit(208), ColorSupport::Simple, "#[fg=brightyellow]x#[default]")]
+    #[case("x".red(), ColorSupport::None, "x")]
+    #[case("a#b".italic(), ColorSupport::True, "#[italics]a##b#[default]")]
+    fn test_tmux<D: DisplayWithBackend>(
+        #[case] value: D,
+        #[case] supported: ColorSupport,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(
+            value.display_with(Tmux::new(supported)).to_string(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_spec() {
+        let value = "x".styled().underline().blue().on_white();
+        assert_eq!(
+            value
+                .display_with(Tmux::new(ColorSupport::True))
+                .to_string(),
+            "#[underscore,fg=blue,bg=white]x#[default]",
+        );
+    }
+}